@@ -0,0 +1,97 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::collections::UnorderedMap;
+use near_sdk::{env, AccountId};
+use std::fmt::Debug;
+
+use crate::ContractError;
+
+// Owner and role bookkeeping shared by the contracts. Each contract points at where it keeps
+// the data and exposes its own methods and events on top.
+pub trait AccessControl {
+    type Role: BorshDeserialize + BorshSerialize + Copy + PartialEq + Debug;
+
+    // Role that may act in place of any other
+    const ADMIN: Self::Role;
+
+    fn owner_id(&self) -> &AccountId;
+    fn owner_id_mut(&mut self) -> &mut AccountId;
+    fn pending_owner_mut(&mut self) -> &mut Option<AccountId>;
+    fn roles_by_account(&self) -> &UnorderedMap<AccountId, Vec<Self::Role>>;
+    fn roles_by_account_mut(&mut self) -> &mut UnorderedMap<AccountId, Vec<Self::Role>>;
+
+    fn assert_owner(&self) {
+        if env::predecessor_account_id() != *self.owner_id() {
+            ContractError::NotOwner.panic();
+        }
+    }
+
+    // The owner implicitly holds every role, and admins may act in place of any other role
+    fn internal_has_role(&self, account_id: &AccountId, role: Self::Role) -> bool {
+        if account_id == self.owner_id() {
+            return true;
+        }
+
+        match self.roles_by_account().get(account_id) {
+            None => false,
+            Some(roles) => roles.contains(&role) || roles.contains(&Self::ADMIN),
+        }
+    }
+
+    fn assert_role(&self, role: Self::Role) {
+        if !self.internal_has_role(&env::predecessor_account_id(), role) {
+            ContractError::MissingRole.panic_with(format!("{:?}", role));
+        }
+    }
+
+    // Returns false if the account already held the role
+    fn internal_grant_role(&mut self, account_id: &AccountId, role: Self::Role) -> bool {
+        let mut roles = self.roles_by_account().get(account_id).unwrap_or_default();
+        if roles.contains(&role) {
+            return false;
+        }
+
+        roles.push(role);
+        self.roles_by_account_mut().insert(account_id, &roles);
+
+        true
+    }
+
+    // Returns false if the account did not hold the role
+    fn internal_revoke_role(&mut self, account_id: &AccountId, role: Self::Role) -> bool {
+        let mut roles = self.roles_by_account().get(account_id).unwrap_or_default();
+        if !roles.contains(&role) {
+            return false;
+        }
+
+        roles.retain(|r| *r != role);
+        if roles.is_empty() {
+            self.roles_by_account_mut().remove(account_id);
+        } else {
+            self.roles_by_account_mut().insert(account_id, &roles);
+        }
+
+        true
+    }
+
+    // Ownership moves in two steps so a typo in the proposed account can't lock the contract
+    fn internal_propose_owner(&mut self, new_owner_id: AccountId) {
+        self.assert_owner();
+
+        *self.pending_owner_mut() = Some(new_owner_id);
+    }
+
+    // Returns the previous owner
+    fn internal_accept_ownership(&mut self) -> AccountId {
+        let pending_owner = match self.pending_owner_mut() {
+            None => ContractError::NoPendingOwner.panic(),
+            Some(account_id) => account_id.clone(),
+        };
+
+        if env::predecessor_account_id() != pending_owner {
+            ContractError::NotPendingOwner.panic();
+        }
+
+        *self.pending_owner_mut() = None;
+        std::mem::replace(self.owner_id_mut(), pending_owner)
+    }
+}
@@ -0,0 +1,189 @@
+use near_sdk::env;
+use std::fmt::Display;
+
+// Errors shared by the `ino` and `nft` contracts. A variant keeps its code forever, so clients
+// can match on the `E<code>:` prefix of the panic message instead of on its wording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractError {
+    // Access control
+    NotOwner = 100,
+    NotPendingOwner = 101,
+    NoPendingOwner = 102,
+    MissingRole = 103,
+    NotDao = 104,
+    NotCollectionCreator = 105,
+    NotBeneficiary = 106,
+    NotTokenOwner = 107,
+    NotLaunchpad = 108,
+
+    // Pausing
+    MintPaused = 200,
+    TransfersPaused = 201,
+    SubmissionsPaused = 202,
+
+    // Sale and payments
+    SoldOut = 300,
+    InsufficientDeposit = 301,
+    PriceTooLarge = 302,
+    WithdrawTooLarge = 303,
+    InsufficientStorageDeposit = 304,
+    StorageNotRegistered = 305,
+    InsufficientStorageBalance = 306,
+    StorageStillInUse = 307,
+    SlashTooLarge = 308,
+    InvalidQuantity = 309,
+    SaleLocked = 310,
+    SaleEnded = 311,
+    RefundUnavailable = 312,
+    NothingToRefund = 313,
+    InvalidSoftCap = 314,
+    InvalidVesting = 315,
+    NoVesting = 316,
+    NothingToClaim = 317,
+    InvalidRaffle = 318,
+    NoRaffle = 319,
+    RaffleClosed = 320,
+    AlreadyEntered = 321,
+    RaffleNotDrawn = 322,
+    RaffleAlreadyDrawn = 323,
+    NoMintAllowance = 324,
+    RaffleOpen = 325,
+    InvalidMintGate = 326,
+    MintGateNotMet = 327,
+    VestingFrozen = 328,
+    ProceedsEscrowed = 329,
+    NoLaunchpad = 330,
+
+    // Token metadata
+    MetadataFrozen = 400,
+    MetadataLocked = 401,
+    MetadataIncomplete = 402,
+    InvalidTemplate = 403,
+    InvalidTokenMetadata = 404,
+    InvalidAttribute = 405,
+    MissingTokenMetadata = 406,
+    DuplicateTokenId = 407,
+    TokenNotFound = 408,
+
+    // INO collections
+    InvalidCollectionId = 500,
+    InvalidCollectionName = 501,
+    InvalidCollectionSymbol = 502,
+    InvalidCollectionUrl = 503,
+    InvalidCollectionTotalCount = 504,
+    InvalidCollectionPrice = 505,
+    InvalidCollectionContract = 506,
+    InvalidDaoAccount = 507,
+    CollectionNotPending = 508,
+    CollectionNotApproved = 509,
+    InvalidFlagReason = 510,
+    ContractAlreadyListed = 511,
+
+    // Contract state and upgrades
+    AlreadyInitialized = 600,
+    NotInitialized = 601,
+    InvalidState = 602,
+    UnknownStateVersion = 603,
+    MissingContractCode = 604,
+    InsufficientGas = 605,
+    MigrationPending = 606,
+    MintedCountOutOfSync = 607,
+}
+
+impl ContractError {
+    pub fn code(self) -> u16 {
+        self as u16
+    }
+
+    pub fn message(self) -> &'static str {
+        match self {
+            ContractError::NotOwner => "Only owner could call this method",
+            ContractError::NotPendingOwner => "Only pending owner could accept ownership",
+            ContractError::NoPendingOwner => "No pending owner",
+            ContractError::MissingRole => "Caller is missing the required role",
+            ContractError::NotDao => "Only DAO could update",
+            ContractError::NotCollectionCreator => "Only the collection creator could call this method",
+            ContractError::NotBeneficiary => "Only the vesting beneficiary could claim",
+            ContractError::NotTokenOwner => "Predecessor must be token owner",
+            ContractError::NotLaunchpad => "Only the launchpad could call this method",
+
+            ContractError::MintPaused => "Minting is paused",
+            ContractError::TransfersPaused => "Transfers are paused",
+            ContractError::SubmissionsPaused => "Submissions are paused",
+
+            ContractError::SoldOut => "All nfts are minted.",
+            ContractError::InsufficientDeposit => "Should be deposit mint price",
+            ContractError::PriceTooLarge => "Mint price is too large",
+            ContractError::WithdrawTooLarge => "Amount exceeds the withdrawable balance",
+            ContractError::InsufficientStorageDeposit => "Must attach more yoctoNEAR to cover storage",
+            ContractError::StorageNotRegistered => "Account is not registered for storage",
+            ContractError::InsufficientStorageBalance => "Storage balance is too low",
+            ContractError::StorageStillInUse => "Account still has storage in use",
+            ContractError::SlashTooLarge => "Slashed amount exceeds the bond",
+            ContractError::InvalidQuantity => "Invalid quantity",
+            ContractError::SaleLocked => "Sale settings are locked once minting started",
+            ContractError::SaleEnded => "Sale has ended",
+            ContractError::RefundUnavailable => "Refunds are only available after a failed raise",
+            ContractError::NothingToRefund => "Nothing to refund for this token",
+            ContractError::InvalidSoftCap => "Invalid soft cap",
+            ContractError::InvalidVesting => "Invalid vesting schedule",
+            ContractError::NoVesting => "No vesting schedule",
+            ContractError::NothingToClaim => "Nothing vested to claim",
+            ContractError::InvalidRaffle => "Invalid raffle",
+            ContractError::NoRaffle => "No raffle",
+            ContractError::RaffleClosed => "Raffle entries are closed",
+            ContractError::AlreadyEntered => "Account already entered the raffle",
+            ContractError::RaffleNotDrawn => "Raffle is not drawn yet",
+            ContractError::RaffleAlreadyDrawn => "Raffle is already drawn",
+            ContractError::NoMintAllowance => "No raffle mint allowance left",
+            ContractError::RaffleOpen => "Raffle entries are still open",
+            ContractError::InvalidMintGate => "Invalid mint gate",
+            ContractError::MintGateNotMet => "Receiver does not hold enough of the gate token",
+            ContractError::VestingFrozen => "Vesting is frozen",
+            ContractError::ProceedsEscrowed => "Tokens can't be burned while their proceeds are in escrow",
+            ContractError::NoLaunchpad => "No launchpad is set",
+
+            ContractError::MetadataFrozen => "Metadata is frozen",
+            ContractError::MetadataLocked => "Metadata is locked once minting started",
+            ContractError::MetadataIncomplete => "Token metadata is incomplete",
+            ContractError::InvalidTemplate => "Invalid metadata template",
+            ContractError::InvalidTokenMetadata => "Invalid token metadata",
+            ContractError::InvalidAttribute => "Invalid token attribute",
+            ContractError::MissingTokenMetadata => "Must provide metadata",
+            ContractError::DuplicateTokenId => "token_id must be unique",
+            ContractError::TokenNotFound => "Token not found",
+
+            ContractError::InvalidCollectionId => "Invalid collection id",
+            ContractError::InvalidCollectionName => "Invalid collection name",
+            ContractError::InvalidCollectionSymbol => "Invalid collection symbol",
+            ContractError::InvalidCollectionUrl => "Invalid collection url",
+            ContractError::InvalidCollectionTotalCount => "Collection total count should be positive",
+            ContractError::InvalidCollectionPrice => "Collection price should be positive",
+            ContractError::InvalidCollectionContract => "Invalid collection contract account id",
+            ContractError::InvalidDaoAccount => "Invalid DAO account id",
+            ContractError::CollectionNotPending => "Collection is not pending",
+            ContractError::CollectionNotApproved => "Collection is not approved",
+            ContractError::InvalidFlagReason => "Invalid flag reason",
+            ContractError::ContractAlreadyListed => "Contract is already linked to another collection",
+
+            ContractError::AlreadyInitialized => "Already initialized",
+            ContractError::NotInitialized => "Contract is not initialized",
+            ContractError::InvalidState => "Cannot deserialize contract state",
+            ContractError::UnknownStateVersion => "Unknown state version",
+            ContractError::MissingContractCode => "New contract code is required",
+            ContractError::InsufficientGas => "Not enough gas attached",
+            ContractError::MigrationPending => "State migration is not finished",
+            ContractError::MintedCountOutOfSync => "Minted count is out of sync with minted ids",
+        }
+    }
+
+    // Panics with `E<code>: <message>`
+    pub fn panic(self) -> ! {
+        env::panic(format!("E{}: {}", self.code(), self.message()).as_bytes())
+    }
+
+    // Panics with `E<code>: <message> (<detail>)`, for errors that need context such as an amount
+    pub fn panic_with<D: Display>(self, detail: D) -> ! {
+        env::panic(format!("E{}: {} ({})", self.code(), self.message(), detail).as_bytes())
+    }
+}
@@ -0,0 +1,14 @@
+use near_sdk::env;
+use near_sdk::serde_json::{self, json, Value};
+
+// Logs an event following the NEP-297 `EVENT_JSON:` format so indexers can pick it up
+pub fn log_event(standard: &str, version: &str, event: &str, data: Value) {
+    let payload = json!({
+        "standard": standard,
+        "version": version,
+        "event": event,
+        "data": data,
+    });
+
+    env::log(format!("EVENT_JSON:{}", serde_json::to_string(&payload).unwrap()).as_bytes());
+}
@@ -0,0 +1,10 @@
+mod access;
+mod errors;
+mod events;
+pub mod state;
+pub mod storage;
+
+pub use crate::access::AccessControl;
+pub use crate::errors::ContractError;
+pub use crate::events::log_event;
+pub use crate::storage::StorageAccount;
@@ -0,0 +1,49 @@
+use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{env, Gas, Promise};
+
+use crate::ContractError;
+
+const STATE_KEY: &[u8] = b"STATE";
+const STATE_VERSION_KEY: &[u8] = b"STATE_VERSION";
+// Least gas `upgrade_self` leaves for `migrate`
+const GAS_FOR_MIGRATE: Gas = 20_000_000_000_000;
+
+// Raw contract state with its layout version. State written before versioning has no version
+// entry and is treated as version 1.
+pub fn read_state() -> (u16, Vec<u8>) {
+    let state = env::storage_read(STATE_KEY).unwrap_or_else(|| ContractError::NotInitialized.panic());
+    let version = match env::storage_read(STATE_VERSION_KEY) {
+        None => 1,
+        Some(data) => u16::try_from_slice(&data).unwrap(),
+    };
+
+    (version, state)
+}
+
+pub fn decode_state<T: BorshDeserialize>(state: &[u8], version: u16) -> T {
+    T::try_from_slice(state).unwrap_or_else(|_| ContractError::InvalidState.panic_with(format!("V{}", version)))
+}
+
+pub fn write_state_version(version: u16) {
+    env::storage_write(STATE_VERSION_KEY, &version.try_to_vec().unwrap());
+}
+
+// Deploys the wasm passed as raw input over the current account and calls `migrate` on it,
+// with all the gas left except `gas_for_upgrade`
+pub fn upgrade_self(gas_for_upgrade: Gas) -> Promise {
+    let code = env::input().unwrap_or_else(|| ContractError::MissingContractCode.panic());
+
+    let required_gas = env::used_gas() + gas_for_upgrade + GAS_FOR_MIGRATE;
+    if env::prepaid_gas() < required_gas {
+        ContractError::InsufficientGas.panic_with(format!("attach at least {} gas", required_gas));
+    }
+
+    Promise::new(env::current_account_id())
+        .deploy_contract(code)
+        .function_call(
+            b"migrate".to_vec(),
+            vec![],
+            0,
+            env::prepaid_gas() - env::used_gas() - gas_for_upgrade,
+        )
+}
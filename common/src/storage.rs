@@ -0,0 +1,96 @@
+use near_contract_standards::storage_management::StorageBalance;
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::json_types::U128;
+use near_sdk::{env, Balance, Promise};
+
+use crate::ContractError;
+
+// NEP-145 storage balance of an account. Each contract keeps these in its own map and decides
+// how many bytes registering takes.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StorageAccount {
+    pub deposit: Balance,
+    pub used_bytes: u64,
+}
+
+impl StorageAccount {
+    pub fn used(&self) -> Balance {
+        Balance::from(self.used_bytes) * env::storage_byte_cost()
+    }
+
+    pub fn available(&self) -> Balance {
+        self.deposit.saturating_sub(self.used())
+    }
+
+    pub fn to_storage_balance(&self) -> StorageBalance {
+        StorageBalance {
+            total: U128(self.deposit),
+            available: U128(self.available()),
+        }
+    }
+
+    // Takes `amount` out of the deposit, everything available when omitted
+    pub fn withdraw(&mut self, amount: Option<U128>) -> Balance {
+        let available = self.available();
+        let amount = amount.map(|amount| amount.0).unwrap_or(available);
+        if amount > available {
+            ContractError::WithdrawTooLarge.panic_with(format!("{} yoctoNEAR available", available));
+        }
+
+        self.deposit -= amount;
+        amount
+    }
+
+    // With `allow_overdraw` the account may end up using more than it deposited
+    pub fn charge(&mut self, bytes: u64, allow_overdraw: bool) {
+        self.used_bytes += bytes;
+
+        if !allow_overdraw && self.used() > self.deposit {
+            ContractError::InsufficientStorageBalance.panic_with(format!(
+                "{} yoctoNEAR required, {} deposited",
+                self.used(),
+                self.deposit
+            ));
+        }
+    }
+
+    // Never goes below what registering took, returns the bytes actually released
+    pub fn release(&mut self, bytes: u64, registration_bytes: u64) -> u64 {
+        let released = bytes.min(self.used_bytes.saturating_sub(registration_bytes));
+        self.used_bytes -= released;
+        released
+    }
+
+    // Accounts can only leave once they use nothing beyond their registration
+    pub fn assert_unregisterable(&self, registration_bytes: u64) {
+        if self.used_bytes > registration_bytes {
+            ContractError::StorageStillInUse.panic();
+        }
+    }
+}
+
+// Part of a `storage_deposit` the account keeps. With `registration_only` anything beyond what
+// registering takes goes back to the caller, and new accounts need at least `min_balance`.
+pub fn storage_deposit_kept(amount: Balance, registered: bool, registration_only: bool, min_balance: Balance) -> Balance {
+    let kept = match (registration_only, registered) {
+        (false, _) => amount,
+        (true, true) => 0,
+        (true, false) => min_balance.min(amount),
+    };
+
+    if !registered && kept < min_balance {
+        ContractError::InsufficientStorageDeposit.panic_with(format!("{} yoctoNEAR required", min_balance));
+    }
+    if amount > kept {
+        Promise::new(env::predecessor_account_id()).transfer(amount - kept);
+    }
+
+    kept
+}
+
+// Holdings can't be dropped on behalf of their owner, so `storage_unregister` never forces
+pub fn assert_unregister_not_forced(force: Option<bool>) {
+    if force.unwrap_or(false) {
+        ContractError::StorageStillInUse.panic_with("force is not supported");
+    }
+}
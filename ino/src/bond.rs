@@ -0,0 +1,88 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[near_bindgen]
+impl INO {
+    // Bond escrowed with every new submission, collections already submitted keep theirs
+    pub fn set_submission_bond(&mut self, bond: U128) {
+        self.assert_owner();
+
+        self.submission_bond = bond.into();
+
+        log_event("submission_bond_updated", json!({ "bond": bond }));
+    }
+
+    pub fn get_submission_bond(&self) -> U128 {
+        U128(self.submission_bond)
+    }
+
+    pub fn get_collection_bond(&self, collection_id: U128) -> U128 {
+        U128(self.bond_by_collection.get(&collection_id.0).unwrap_or(0))
+    }
+
+    // Rejects a pending collection for a policy violation. `slash` of its bond goes to the treasury,
+    // the whole bond when omitted, and the rest is refunded to the creator.
+    // Curators can reject even when a DAO approves collections, and so can the DAO.
+    pub fn reject_collection(&mut self, collection_id: U128, slash: Option<U128>, reason: String) {
+        let is_dao = self.dao.as_ref().map_or(false, |config| env::predecessor_account_id() == config.account_id);
+        if !is_dao {
+            self.assert_role(Role::Curator);
+        }
+
+        let collection_id: u128 = collection_id.into();
+        self.assert_collection_pending(collection_id);
+
+        let bond = self.bond_by_collection.get(&collection_id).unwrap_or(0);
+        let slashed = slash.map(|slash| slash.0).unwrap_or(bond);
+        if slashed > bond {
+            ContractError::SlashTooLarge.panic_with(format!("bond is {} yoctoNEAR", bond));
+        }
+
+        self.status_by_id.insert(&collection_id, &CollectionStatus::Rejected);
+        self.internal_settle_bond(collection_id, slashed);
+
+        log_event("collection_rejected", json!({
+            "collection_id": U128(collection_id),
+            "reason": reason,
+            "by": env::predecessor_account_id(),
+        }));
+    }
+}
+
+impl INO {
+    pub(crate) fn assert_collection_pending(&self, collection_id: u128) {
+        match self.status_by_id.get(&collection_id) {
+            None => ContractError::InvalidCollectionId.panic(),
+            Some(CollectionStatus::Pending) => {},
+            Some(_) => ContractError::CollectionNotPending.panic(),
+        }
+    }
+
+    // Releases the escrowed bond: `slashed` goes to the treasury and the rest back to the creator
+    pub(crate) fn internal_settle_bond(&mut self, collection_id: u128, slashed: Balance) {
+        let initial_storage_usage = env::storage_usage();
+
+        let bond = match self.bond_by_collection.remove(&collection_id) {
+            None => return,
+            Some(bond) => bond,
+        };
+        // bonds are only taken by `add_collection`, which records the creator
+        let creator_id = self.creator_by_id.get(&collection_id).unwrap();
+
+        self.internal_release_storage(&creator_id, initial_storage_usage - env::storage_usage());
+
+        self.treasury_balance += slashed;
+        let refund = bond - slashed;
+        if refund > 0 {
+            Promise::new(creator_id.clone()).transfer(refund);
+        }
+
+        log_event("bond_settled", json!({
+            "collection_id": U128(collection_id),
+            "creator_id": creator_id,
+            "refunded": U128(refund),
+            "slashed": U128(slashed),
+        }));
+    }
+}
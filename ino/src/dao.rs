@@ -0,0 +1,313 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json};
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+const GAS_FOR_ADD_PROPOSAL: Gas = 20_000_000_000_000;
+const GAS_FOR_ON_PROPOSAL_CREATED: Gas = 10_000_000_000_000;
+const GAS_FOR_GET_PROPOSAL: Gas = 5_000_000_000_000;
+const GAS_FOR_ON_PROPOSAL_STATUS: Gas = 10_000_000_000_000;
+// Gas the DAO attaches when it executes the approved proposal
+const GAS_FOR_STATUS_UPDATE: Gas = 20_000_000_000_000;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DaoConfig {
+    // Sputnik-style DAO allowed to approve collections
+    pub account_id: AccountId,
+    // Bond the DAO requires when a proposal is added, paid by the submitter
+    pub proposal_bond: U128,
+    // Whether `add_collection` opens an approval proposal on the DAO
+    pub auto_propose: bool,
+}
+
+// How `DaoConfig` is kept in state
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct StoredDaoConfig {
+    pub account_id: AccountId,
+    pub proposal_bond: Balance,
+    pub auto_propose: bool,
+}
+
+impl From<DaoConfig> for StoredDaoConfig {
+    fn from(config: DaoConfig) -> Self {
+        Self { account_id: config.account_id, proposal_bond: config.proposal_bond.0, auto_propose: config.auto_propose }
+    }
+}
+
+impl From<&StoredDaoConfig> for DaoConfig {
+    fn from(config: &StoredDaoConfig) -> Self {
+        Self {
+            account_id: config.account_id.clone(),
+            proposal_bond: U128(config.proposal_bond),
+            auto_propose: config.auto_propose,
+        }
+    }
+}
+
+// The DAO hands the proposal bond back to its proposer, which is this contract, so it is
+// recorded for the submitter until it can be forwarded
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ProposalBond {
+    pub submitter_id: AccountId,
+    pub amount: Balance,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalInput {
+    description: String,
+    kind: ProposalKind,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalKind {
+    FunctionCall {
+        receiver_id: AccountId,
+        actions: Vec<ActionCall>,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActionCall {
+    method_name: String,
+    args: Base64VecU8,
+    deposit: U128,
+    gas: U64,
+}
+
+// Part of Sputnik's `get_proposal` answer, other fields are ignored
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalOutput {
+    pub status: ProposalStatus,
+}
+
+#[derive(Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    InProgress,
+    Approved,
+    Rejected,
+    Removed,
+    Expired,
+    Moved,
+    Failed,
+}
+
+#[ext_contract(ext_dao)]
+pub trait SputnikDao {
+    fn add_proposal(&mut self, proposal: ProposalInput) -> u64;
+    fn get_proposal(&self, id: u64) -> ProposalOutput;
+}
+
+#[ext_contract(ext_self)]
+pub trait DaoCallbacks {
+    fn on_proposal_created(&mut self, collection_id: U128) -> Option<U64>;
+    fn on_proposal_status(&mut self, collection_id: U128) -> bool;
+}
+
+#[near_bindgen]
+impl INO {
+    pub fn set_dao_config(&mut self, config: Option<DaoConfig>) {
+        self.assert_owner();
+
+        if let Some(config) = &config {
+            if !env::is_valid_account_id(config.account_id.as_bytes()) {
+                ContractError::InvalidDaoAccount.panic();
+            }
+        }
+
+        self.dao = config.map(StoredDaoConfig::from);
+
+        log_event("dao_config_updated", json!({ "config": self.get_dao_config() }));
+    }
+
+    pub fn get_dao_config(&self) -> Option<DaoConfig> {
+        self.dao.as_ref().map(DaoConfig::from)
+    }
+
+    pub fn get_proposal_id(&self, collection_id: U128) -> Option<U64> {
+        self.proposal_id_by_collection.get(&collection_id.0).map(U64)
+    }
+
+    pub fn get_proposal_bond(&self, collection_id: U128) -> U128 {
+        U128(self.proposal_bond_by_collection.get(&collection_id.0).map_or(0, |bond| bond.amount))
+    }
+
+    // Anyone can hand a proposal bond back to its submitter once the DAO has returned it, which
+    // Sputnik does when a proposal is approved, rejected or expired. A removed proposal keeps its bond.
+    pub fn release_proposal_bond(&mut self, collection_id: U128) -> Promise {
+        let config = self.dao.as_ref().unwrap_or_else(|| ContractError::NotDao.panic_with("no DAO configured"));
+        if self.proposal_bond_by_collection.get(&collection_id.0).is_none() {
+            ContractError::NothingToRefund.panic();
+        }
+        let proposal_id = self
+            .proposal_id_by_collection
+            .get(&collection_id.0)
+            .unwrap_or_else(|| ContractError::NothingToRefund.panic_with("no proposal"));
+
+        ext_dao::get_proposal(proposal_id, &config.account_id, 0, GAS_FOR_GET_PROPOSAL).then(
+            ext_self::on_proposal_status(collection_id, &env::current_account_id(), 0, GAS_FOR_ON_PROPOSAL_STATUS),
+        )
+    }
+
+    #[private]
+    pub fn on_proposal_created(&mut self, collection_id: U128) -> Option<U64> {
+        let proposal_id = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<u64>(&value).ok(),
+            _ => {
+                // The bond came back to this contract, hand it back to the submitter
+                self.internal_forward_proposal_bond(collection_id.0);
+
+                log_event("proposal_failed", json!({ "collection_id": collection_id }));
+
+                return None;
+            },
+        };
+
+        match proposal_id {
+            Some(proposal_id) => {
+                self.proposal_id_by_collection.insert(&collection_id.0, &proposal_id);
+
+                log_event("proposal_created", json!({ "collection_id": collection_id, "proposal_id": U64(proposal_id) }));
+
+                Some(U64(proposal_id))
+            },
+            None => {
+                // The DAO kept the bond but its answer is not a Sputnik proposal id, so there is
+                // no proposal to follow and the bond can't be recovered
+                self.internal_remove_proposal_bond(collection_id.0);
+
+                log_event("proposal_unknown", json!({ "collection_id": collection_id }));
+
+                None
+            },
+        }
+    }
+
+    #[private]
+    pub fn on_proposal_status(&mut self, collection_id: U128) -> bool {
+        let status = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<ProposalOutput>(&value).ok().map(|output| output.status),
+            _ => None,
+        };
+
+        match status {
+            Some(ProposalStatus::Approved) | Some(ProposalStatus::Rejected) | Some(ProposalStatus::Expired) => {
+                self.internal_forward_proposal_bond(collection_id.0);
+                true
+            },
+            Some(ProposalStatus::Removed) => {
+                let bond = self.internal_remove_proposal_bond(collection_id.0);
+
+                log_event("proposal_bond_forfeited", json!({ "collection_id": collection_id, "amount": U128(bond) }));
+
+                false
+            },
+            // still open or unknown, the bond stays recorded
+            _ => false,
+        }
+    }
+}
+
+impl INO {
+    // Once a DAO is configured it is the only account that can approve collections
+    pub(crate) fn assert_can_update_status(&self) {
+        match &self.dao {
+            Some(config) => {
+                if env::predecessor_account_id() != config.account_id {
+                    ContractError::NotDao.panic();
+                }
+            },
+            None => self.assert_role(Role::Curator),
+        }
+    }
+
+    pub(crate) fn proposal_bond(&self) -> Balance {
+        match &self.dao {
+            Some(config) if config.auto_propose => config.proposal_bond,
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn internal_create_proposal(&self, collection_id: u128, collection: &Collection) -> Option<Promise> {
+        let config = match &self.dao {
+            Some(config) if config.auto_propose => config,
+            _ => return None,
+        };
+
+        let args = json!({ "arg_collection_id": U128(collection_id) });
+
+        let proposal = ProposalInput {
+            description: format!("Approve INO collection #{} {} ({})", collection_id, collection.name, collection.url),
+            kind: ProposalKind::FunctionCall {
+                receiver_id: env::current_account_id(),
+                actions: vec![ActionCall {
+                    method_name: "update_collection_status".to_string(),
+                    args: Base64VecU8(serde_json::to_vec(&args).unwrap()),
+                    deposit: U128(0),
+                    gas: U64(GAS_FOR_STATUS_UPDATE),
+                }],
+            },
+        };
+
+        Some(
+            ext_dao::add_proposal(
+                proposal,
+                &config.account_id,
+                config.proposal_bond,
+                GAS_FOR_ADD_PROPOSAL,
+            )
+            .then(ext_self::on_proposal_created(
+                U128(collection_id),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_ON_PROPOSAL_CREATED,
+            ))
+        )
+    }
+
+    // Recorded with the collection so its storage is charged to the submitter
+    pub(crate) fn internal_record_proposal_bond(&mut self, collection_id: u128, submitter_id: &AccountId) {
+        let amount = self.proposal_bond();
+        if amount > 0 {
+            self.proposal_bond_by_collection.insert(&collection_id, &ProposalBond {
+                submitter_id: submitter_id.clone(),
+                amount,
+            });
+        }
+    }
+
+    fn internal_forward_proposal_bond(&mut self, collection_id: u128) {
+        let submitter_id = match self.proposal_bond_by_collection.get(&collection_id) {
+            None => return,
+            Some(bond) => bond.submitter_id,
+        };
+        let amount = self.internal_remove_proposal_bond(collection_id);
+
+        Promise::new(submitter_id.clone()).transfer(amount);
+
+        log_event("proposal_bond_released", json!({
+            "collection_id": U128(collection_id),
+            "submitter_id": submitter_id,
+            "amount": U128(amount),
+        }));
+    }
+
+    fn internal_remove_proposal_bond(&mut self, collection_id: u128) -> Balance {
+        let initial_storage_usage = env::storage_usage();
+
+        let bond = match self.proposal_bond_by_collection.remove(&collection_id) {
+            None => return 0,
+            Some(bond) => bond,
+        };
+
+        // the record keeps the submitter registered until it is gone
+        self.internal_release_storage(&bond.submitter_id, initial_storage_usage - env::storage_usage());
+
+        bond.amount
+    }
+}
@@ -0,0 +1,78 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+const GAS_FOR_SET_VESTING_FROZEN: Gas = 10_000_000_000_000;
+const GAS_FOR_ON_VESTING_FROZEN: Gas = 10_000_000_000_000;
+// Reasons are kept in state, paid for by the registry
+const MAX_FLAG_REASON_LENGTH: usize = 280;
+
+#[ext_contract(ext_nft_vesting)]
+pub trait NftVesting {
+    fn set_vesting_frozen(&mut self, frozen: bool);
+}
+
+#[ext_contract(ext_self)]
+pub trait FlagCallbacks {
+    fn on_vesting_frozen(&mut self, collection_id: U128, flagged: bool, reason: String, curator_id: AccountId) -> bool;
+}
+
+#[near_bindgen]
+impl INO {
+    // Flagging an approved collection freezes the unvested proceeds on its contract, which has to
+    // list this registry as its launchpad. The flag only sticks once the contract froze them.
+    pub fn flag_collection(&mut self, collection_id: U128, flagged: bool, reason: String) -> Promise {
+        self.assert_role(Role::Curator);
+
+        let collection = self
+            .collection_by_id
+            .get(&collection_id.0)
+            .unwrap_or_else(|| ContractError::InvalidCollectionId.panic());
+        if !self.status_by_id.get(&collection_id.0).map_or(false, CollectionStatus::is_approved) {
+            ContractError::CollectionNotApproved.panic();
+        }
+        if reason.len() > MAX_FLAG_REASON_LENGTH {
+            ContractError::InvalidFlagReason.panic_with(format!("at most {} bytes", MAX_FLAG_REASON_LENGTH));
+        }
+
+        ext_nft_vesting::set_vesting_frozen(flagged, &collection.contract, 0, GAS_FOR_SET_VESTING_FROZEN).then(
+            ext_self::on_vesting_frozen(
+                collection_id,
+                flagged,
+                reason,
+                env::predecessor_account_id(),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_ON_VESTING_FROZEN,
+            ),
+        )
+    }
+
+    #[private]
+    pub fn on_vesting_frozen(&mut self, collection_id: U128, flagged: bool, reason: String, curator_id: AccountId) -> bool {
+        if !matches!(env::promise_result(0), PromiseResult::Successful(_)) {
+            log_event("collection_flag_failed", json!({ "collection_id": collection_id, "flagged": flagged }));
+            return false;
+        }
+
+        if flagged {
+            self.flag_reason_by_collection.insert(&collection_id.0, &reason);
+        } else {
+            self.flag_reason_by_collection.remove(&collection_id.0);
+        }
+
+        log_event("collection_flagged", json!({
+            "collection_id": collection_id,
+            "flagged": flagged,
+            "reason": reason,
+            "by": curator_id,
+        }));
+
+        true
+    }
+
+    pub fn get_flag_reason(&self, collection_id: U128) -> Option<String> {
+        self.flag_reason_by_collection.get(&collection_id.0)
+    }
+}
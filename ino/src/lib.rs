@@ -1,19 +1,113 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::collections::*;
+use near_sdk::json_types::*;
 use near_sdk::{
     env, near_bindgen, PanicOnDefault, Balance, Promise, AccountId
 };
+use near_sdk::serde_json::json;
+use ino_common::{AccessControl, ContractError, StorageAccount};
 
-#[derive(Debug, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+mod bond;
+mod dao;
+mod events;
+mod flag;
+mod ownership;
+mod pause;
+mod roles;
+mod sale;
+mod stats;
+mod storage;
+mod treasury;
+mod upgrade;
+mod validation;
+
+pub use crate::dao::DaoConfig;
+use crate::dao::{ProposalBond, StoredDaoConfig};
+pub use crate::pause::PauseStatus;
+pub use crate::roles::Role;
+pub use crate::stats::CollectionStatsView;
+use crate::stats::CollectionStats;
+use crate::events::log_event;
+
+// A collection as it is submitted and listed
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Collection {
-    name: String,
-    symbol: String,
-    url: String,
-    total_count: u128,
-    price: Balance,
-    contract: String
+    pub name: String,
+    pub symbol: String,
+    pub url: String,
+    pub total_count: U128,
+    pub price: U128,
+    pub contract: String
+}
+
+// How a collection is kept in state
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct StoredCollection {
+    pub name: String,
+    pub symbol: String,
+    pub url: String,
+    pub total_count: u128,
+    pub price: Balance,
+    pub contract: String,
+}
+
+impl From<&Collection> for StoredCollection {
+    fn from(collection: &Collection) -> Self {
+        Self {
+            name: collection.name.clone(),
+            symbol: collection.symbol.clone(),
+            url: collection.url.clone(),
+            total_count: collection.total_count.0,
+            price: collection.price.0,
+            contract: collection.contract.clone(),
+        }
+    }
+}
+
+impl From<StoredCollection> for Collection {
+    fn from(collection: StoredCollection) -> Self {
+        Self {
+            name: collection.name,
+            symbol: collection.symbol,
+            url: collection.url,
+            total_count: U128(collection.total_count),
+            price: U128(collection.price),
+            contract: collection.contract,
+        }
+    }
+}
+
+// Replaces the former `bool` status, `Pending` and `Approved` keep its Borsh encoding
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum CollectionStatus {
+    Pending,
+    Approved,
+    Rejected,
+    // Approved and every token is minted
+    SoldOut,
+}
+
+impl CollectionStatus {
+    pub fn is_approved(self) -> bool {
+        matches!(self, CollectionStatus::Approved | CollectionStatus::SoldOut)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CollectionView {
+    pub id: U128,
+    pub collection: Collection,
+    pub approved: bool,
+    pub status: CollectionStatus,
+    pub creator: Option<AccountId>,
+    pub bond: U128,
+    pub sold: U128,
+    pub stats: CollectionStatsView,
+    pub flagged: bool,
 }
 
 #[near_bindgen]
@@ -21,61 +115,155 @@ pub struct Collection {
 pub struct INO {
     owner: AccountId,
     collection_count: u128,
-    collection_by_id: LookupMap<u128, Collection>,
-    status_by_id: UnorderedMap<u128, bool>,
+    collection_by_id: LookupMap<u128, StoredCollection>,
+    status_by_id: UnorderedMap<u128, CollectionStatus>,
+    submissions_paused: bool,
+    roles_by_account: UnorderedMap<AccountId, Vec<Role>>,
+    pending_owner: Option<AccountId>,
+    dao: Option<StoredDaoConfig>,
+    proposal_id_by_collection: LookupMap<u128, u64>,
+    storage_accounts: LookupMap<AccountId, StorageAccount>,
+    creator_by_id: LookupMap<u128, AccountId>,
+    submission_bond: Balance,
+    bond_by_collection: LookupMap<u128, Balance>,
+    treasury_balance: Balance,
+    sold_by_collection: LookupMap<u128, u128>,
+    stats_by_collection: LookupMap<u128, CollectionStats>,
+    collection_id_by_contract: LookupMap<AccountId, u128>,
+    flag_reason_by_collection: LookupMap<u128, String>,
+    proposal_bond_by_collection: LookupMap<u128, ProposalBond>,
 }
 
 #[near_bindgen]
 impl INO {
     #[init]
     pub fn new() -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+        if env::state_exists() {
+            ContractError::AlreadyInitialized.panic();
+        }
+
+        upgrade::write_state_version();
 
         Self {
             owner: env::predecessor_account_id(),
             collection_count: 0,
             collection_by_id: LookupMap::new(b"collection_by_id".to_vec()), 
             status_by_id: UnorderedMap::new(b"status_by_id".to_vec()), 
+            submissions_paused: false,
+            roles_by_account: UnorderedMap::new(b"roles_by_account".to_vec()),
+            pending_owner: None,
+            dao: None,
+            proposal_id_by_collection: LookupMap::new(b"proposal_id_by_collection".to_vec()),
+            storage_accounts: LookupMap::new(b"storage_accounts".to_vec()),
+            creator_by_id: LookupMap::new(b"creator_by_id".to_vec()),
+            submission_bond: 0,
+            bond_by_collection: LookupMap::new(b"bond_by_collection".to_vec()),
+            treasury_balance: 0,
+            sold_by_collection: LookupMap::new(b"sold_by_collection".to_vec()),
+            stats_by_collection: LookupMap::new(b"stats_by_collection".to_vec()),
+            collection_id_by_contract: LookupMap::new(b"collection_id_by_contract".to_vec()),
+            flag_reason_by_collection: LookupMap::new(b"flag_reason_by_collection".to_vec()),
+            proposal_bond_by_collection: LookupMap::new(b"proposal_bond_by_collection".to_vec()),
         }
     }
 
+    // The attached deposit pays the DAO proposal bond and the submission bond, anything above goes
+    // to the creator's storage balance, registering them if needed. Storage is then taken from that balance.
     #[payable]
     pub fn add_collection(
         &mut self,
         new_collection: Collection
     ) {
+        self.assert_submissions_not_paused();
+        new_collection.assert_valid();
+
+        let creator_id = env::predecessor_account_id();
+        let bonds = self.proposal_bond() + self.submission_bond;
+        let attached_deposit = env::attached_deposit();
+        if attached_deposit < bonds {
+            ContractError::InsufficientDeposit.panic_with(format!("{} yoctoNEAR in bonds required", bonds));
+        }
+        self.internal_deposit_for_storage(&creator_id, attached_deposit - bonds);
+
         let initial_storage_usage = env::storage_usage();
 
         let new_id: u128 = self.collection_count;
 
-        self.collection_by_id.insert(&new_id, &new_collection);
-        self.status_by_id.insert(&new_id, &false);
+        self.collection_by_id.insert(&new_id, &StoredCollection::from(&new_collection));
+        self.status_by_id.insert(&new_id, &CollectionStatus::Pending);
+        self.creator_by_id.insert(&new_id, &creator_id);
+        if self.submission_bond > 0 {
+            self.bond_by_collection.insert(&new_id, &self.submission_bond);
+        }
+        self.internal_record_proposal_bond(new_id, &creator_id);
 
         self.collection_count += 1;
 
         let required_storage_in_bytes = env::storage_usage() - initial_storage_usage;
 
-        refund_deposit(required_storage_in_bytes);
+        self.internal_charge_storage(&creator_id, required_storage_in_bytes);
+
+        self.internal_create_proposal(new_id, &new_collection);
+    }
+
+    // Lets the creator take back a collection that was not approved, refunding what is left of its
+    // bond and freeing its storage
+    pub fn remove_collection(&mut self, collection_id: U128) {
+        let collection_id: u128 = collection_id.into();
+
+        if self.collection_by_id.get(&collection_id).is_none() {
+            ContractError::InvalidCollectionId.panic();
+        }
+
+        let creator_id = self.creator_by_id.get(&collection_id);
+        if creator_id.as_ref() != Some(&env::predecessor_account_id()) {
+            ContractError::NotCollectionCreator.panic();
+        }
+        let creator_id = creator_id.unwrap();
+
+        if self.status_by_id.get(&collection_id).map_or(false, CollectionStatus::is_approved) {
+            ContractError::CollectionNotPending.panic();
+        }
+
+        self.internal_settle_bond(collection_id, 0);
+
+        let initial_storage_usage = env::storage_usage();
+
+        self.collection_by_id.remove(&collection_id);
+        self.status_by_id.remove(&collection_id);
+        self.creator_by_id.remove(&collection_id);
+
+        let released_storage_in_bytes = initial_storage_usage - env::storage_usage();
+
+        self.internal_release_storage(&creator_id, released_storage_in_bytes);
     }
 
     pub fn update_collection_status(
         &mut self,
-        arg_collection_id: Option<u128>
+        arg_collection_id: Option<U128>
     ) {
-        if env::predecessor_account_id() != self.owner {
-            env::panic(b"Only owner could update");
-        }
+        self.assert_can_update_status();
 
         let collection_id: u128 = match arg_collection_id {
-            None => self.collection_count - 1,
-            _ => arg_collection_id.unwrap()
+            None => self.collection_count
+                .checked_sub(1)
+                .unwrap_or_else(|| ContractError::InvalidCollectionId.panic_with("no collection submitted yet")),
+            _ => arg_collection_id.unwrap().into()
         };
 
-        if collection_id < 0 || collection_id >= self.collection_count {
-            env::panic(b"Invalid collection id");
+        self.assert_collection_pending(collection_id);
+
+        // lets the linked contract report its mints, which only works for one collection
+        let collection = self.collection_by_id.get(&collection_id).unwrap();
+        if let Some(listed_id) = self.collection_id_by_contract.get(&collection.contract) {
+            ContractError::ContractAlreadyListed.panic_with(format!("collection {}", listed_id));
         }
+        self.collection_id_by_contract.insert(&collection.contract, &collection_id);
+
+        self.status_by_id.insert(&collection_id, &CollectionStatus::Approved);
+        self.internal_settle_bond(collection_id, 0);
 
-        self.status_by_id.insert(&collection_id, &true);
+        log_event("collection_approved", json!({ "collection_id": U128(collection_id) }));
     }
 
     pub fn get_collection(&self) -> (Vec<Collection>, Vec<bool>) {
@@ -85,39 +273,38 @@ impl INO {
         let count = self.collection_count;
 
         for id in 0..count {
-            match self.collection_by_id.get(&id).unwrap() {
-                data => collections.push(data),
-                _ => {},
-            };
-
-            match self.status_by_id.get(&id) {
-                Some(data) => status.push(data),
-                _ => {},
-            };
+            // removed collections leave a gap in the ids
+            if let Some(data) = self.collection_by_id.get(&id) {
+                collections.push(data.into());
+                status.push(self.status_by_id.get(&id).map_or(false, CollectionStatus::is_approved));
+            }
         }
 
         (collections, status)
     }
-}
 
-pub(crate) fn refund_deposit(storage_used: u64) {
-    //get how much it would cost to store the information
-    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
-    //get the attached deposit
-    let attached_deposit = env::attached_deposit();
-
-    //make sure that the attached deposit is greater than or equal to the required cost
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
-
-    //get the refund amount from the attached deposit - required cost
-    let refund = attached_deposit - required_cost;
-
-    //if the refund is greater than 1 yocto NEAR, we refund the predecessor that amount
-    if refund > 1 {
-        Promise::new(env::predecessor_account_id()).transfer(refund);
+    pub fn get_collections(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<CollectionView> {
+        let start: u128 = from_index.map(|index| index.0).unwrap_or(0);
+        let limit = limit.unwrap_or(50) as usize;
+
+        (start..self.collection_count)
+            .filter_map(|id| {
+                self.collection_by_id.get(&id).map(|collection| {
+                    let status = self.status_by_id.get(&id).unwrap_or(CollectionStatus::Pending);
+                    CollectionView {
+                        id: U128(id),
+                        collection: collection.into(),
+                        approved: status.is_approved(),
+                        status,
+                        creator: self.creator_by_id.get(&id),
+                        bond: U128(self.bond_by_collection.get(&id).unwrap_or(0)),
+                        sold: U128(self.internal_sold(id)),
+                        stats: self.internal_stats(id).into(),
+                        flagged: self.flag_reason_by_collection.get(&id).is_some(),
+                    }
+                })
+            })
+            .take(limit)
+            .collect()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,34 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseStatus {
+    pub submissions_paused: bool,
+}
+
+#[near_bindgen]
+impl INO {
+    pub fn set_submissions_paused(&mut self, paused: bool) {
+        self.assert_role(Role::Pauser);
+
+        self.submissions_paused = paused;
+
+        log_event("submissions_paused", json!({ "paused": paused, "by": env::predecessor_account_id() }));
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        PauseStatus {
+            submissions_paused: self.submissions_paused,
+        }
+    }
+}
+
+impl INO {
+    pub(crate) fn assert_submissions_not_paused(&self) {
+        if self.submissions_paused {
+            ContractError::SubmissionsPaused.panic();
+        }
+    }
+}
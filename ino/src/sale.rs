@@ -0,0 +1,228 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json, Value};
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+// Keeps a batch of gated mints within the 300 Tgas a transaction can attach
+const MAX_BUY_QUANTITY: u32 = 5;
+
+const GAS_FOR_GET_MINT_COST: Gas = 5_000_000_000_000;
+const GAS_FOR_STORAGE_DEPOSIT: Gas = 5_000_000_000_000;
+// Includes the mint report the linked contract sends back, and the gate check and its callback
+// when the collection is gated
+const GAS_FOR_NFT_MINT: Gas = 45_000_000_000_000;
+const GAS_FOR_ON_BUY: Gas = 10_000_000_000_000;
+// What `on_mint_cost` needs for itself besides the gas it hands on
+const GAS_FOR_ON_MINT_COST: Gas = 15_000_000_000_000;
+
+const NO_DEPOSIT: Balance = 0;
+
+// Answer of the linked contract's `get_mint_cost`
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintCost {
+    pub price: U128,
+    pub storage: U128,
+    pub total: U128,
+    // Missing from contracts that predate mint gates
+    #[serde(default)]
+    pub gated: bool,
+}
+
+#[ext_contract(ext_nft)]
+pub trait NftSale {
+    fn get_mint_cost(&self, receiver_id: AccountId, quantity: Option<u32>) -> MintCost;
+}
+
+#[ext_contract(ext_self)]
+pub trait SaleCallbacks {
+    fn on_mint_cost(
+        &mut self,
+        collection_id: U128,
+        buyer_id: AccountId,
+        receiver_id: AccountId,
+        quantity: u32,
+        deposit: U128,
+    ) -> bool;
+
+    fn on_buy(&mut self, collection_id: U128, buyer_id: AccountId, quantity: u32, cost: U128, storage: U128) -> bool;
+}
+
+#[near_bindgen]
+impl INO {
+    // Mints `quantity` tokens of an approved collection to `receiver_id`. The linked contract
+    // prices the mint first, then everything is minted in one batch so it fails or succeeds as a whole.
+    // Gated collections only sell one token per call, as their gate may turn down any mint of a batch.
+    // Whatever is not spent is refunded to the caller.
+    #[payable]
+    pub fn buy(&mut self, collection_id: U128, receiver_id: ValidAccountId, quantity: u32) -> Promise {
+        let collection = self.internal_live_collection(collection_id.0);
+
+        if quantity == 0 || quantity > MAX_BUY_QUANTITY {
+            ContractError::InvalidQuantity.panic_with(format!("between 1 and {}", MAX_BUY_QUANTITY));
+        }
+        if self.internal_sold(collection_id.0) + u128::from(quantity) > collection.total_count {
+            ContractError::SoldOut.panic();
+        }
+
+        let required_gas = GAS_FOR_GET_MINT_COST
+            + GAS_FOR_ON_MINT_COST
+            + GAS_FOR_STORAGE_DEPOSIT
+            + GAS_FOR_NFT_MINT * Gas::from(quantity)
+            + GAS_FOR_ON_BUY;
+        if env::prepaid_gas() < required_gas {
+            ContractError::InsufficientGas.panic_with(format!("attach at least {} gas", required_gas));
+        }
+
+        ext_nft::get_mint_cost(
+            receiver_id.clone().into(),
+            Some(quantity),
+            &collection.contract,
+            NO_DEPOSIT,
+            GAS_FOR_GET_MINT_COST,
+        )
+        .then(ext_self::on_mint_cost(
+            collection_id,
+            env::predecessor_account_id(),
+            receiver_id.into(),
+            quantity,
+            U128(env::attached_deposit()),
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - env::used_gas() - GAS_FOR_GET_MINT_COST,
+        ))
+    }
+
+    #[private]
+    pub fn on_mint_cost(
+        &mut self,
+        collection_id: U128,
+        buyer_id: AccountId,
+        receiver_id: AccountId,
+        quantity: u32,
+        deposit: U128,
+    ) -> bool {
+        let cost = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<MintCost>(&value).ok(),
+            _ => None,
+        };
+
+        let cost = match cost {
+            Some(cost) if cost.total.0 <= deposit.0 && (!cost.gated || quantity == 1) => cost,
+            _ => {
+                if deposit.0 > 0 {
+                    Promise::new(buyer_id.clone()).transfer(deposit.0);
+                }
+                log_event("buy_failed", json!({ "collection_id": collection_id, "buyer_id": buyer_id }));
+                return false;
+            },
+        };
+
+        if deposit.0 > cost.total.0 {
+            Promise::new(buyer_id.clone()).transfer(deposit.0 - cost.total.0);
+        }
+
+        let collection = self.collection_by_id.get(&collection_id.0).unwrap();
+        let mut mint = Promise::new(collection.contract);
+        if cost.storage.0 > 0 {
+            mint = mint.function_call(
+                b"storage_deposit".to_vec(),
+                json!({ "account_id": receiver_id }).to_string().into_bytes(),
+                cost.storage.0,
+                GAS_FOR_STORAGE_DEPOSIT,
+            );
+        }
+        let price = cost.price.0 / Balance::from(quantity);
+        for _ in 0..quantity {
+            mint = mint.function_call(
+                b"nft_mint".to_vec(),
+                json!({ "receiver_id": receiver_id }).to_string().into_bytes(),
+                price,
+                GAS_FOR_NFT_MINT,
+            );
+        }
+
+        mint.then(ext_self::on_buy(
+            collection_id,
+            buyer_id,
+            quantity,
+            cost.total,
+            cost.storage,
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_ON_BUY,
+        ));
+
+        true
+    }
+
+    // A gated collection answers `null` when its gate turned the receiver down. Its mints then sent
+    // their deposits back here while the storage deposit stays on the receiver's storage balance.
+    #[private]
+    pub fn on_buy(&mut self, collection_id: U128, buyer_id: AccountId, quantity: u32, cost: U128, storage: U128) -> bool {
+        let refund = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                if serde_json::from_slice::<Value>(&value).map_or(false, |token| token.is_null()) {
+                    Some(cost.0 - storage.0)
+                } else {
+                    None
+                }
+            },
+            // the batch is undone as a whole and the deposit came back here
+            _ => Some(cost.0),
+        };
+
+        match refund {
+            None => {
+                let sold = self.internal_sold(collection_id.0) + u128::from(quantity);
+                self.sold_by_collection.insert(&collection_id.0, &sold);
+
+                log_event("bought", json!({
+                    "collection_id": collection_id,
+                    "buyer_id": buyer_id,
+                    "quantity": quantity,
+                    "cost": cost,
+                }));
+
+                true
+            },
+            Some(refund) => {
+                if refund > 0 {
+                    Promise::new(buyer_id.clone()).transfer(refund);
+                }
+
+                log_event("buy_failed", json!({ "collection_id": collection_id, "buyer_id": buyer_id }));
+
+                false
+            },
+        }
+    }
+
+    pub fn get_sold(&self, collection_id: U128) -> U128 {
+        U128(self.internal_sold(collection_id.0))
+    }
+}
+
+impl INO {
+    pub(crate) fn internal_live_collection(&self, collection_id: u128) -> StoredCollection {
+        let collection = self
+            .collection_by_id
+            .get(&collection_id)
+            .unwrap_or_else(|| ContractError::InvalidCollectionId.panic());
+
+        match self.status_by_id.get(&collection_id) {
+            Some(CollectionStatus::Approved) => {},
+            Some(CollectionStatus::SoldOut) => ContractError::SoldOut.panic(),
+            _ => ContractError::CollectionNotApproved.panic(),
+        }
+        if self.internal_sold(collection_id) >= collection.total_count {
+            ContractError::SoldOut.panic();
+        }
+
+        collection
+    }
+
+    pub(crate) fn internal_sold(&self, collection_id: u128) -> u128 {
+        self.sold_by_collection.get(&collection_id).unwrap_or(0)
+    }
+}
@@ -0,0 +1,121 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json};
+use near_sdk::{ext_contract, Gas, PromiseResult};
+
+const GAS_FOR_GET_MINTED: Gas = 5_000_000_000_000;
+const GAS_FOR_ON_REFRESH_STATS: Gas = 10_000_000_000_000;
+
+const NO_DEPOSIT: Balance = 0;
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct CollectionStats {
+    pub minted: u128,
+    pub revenue: Balance,
+    pub updated_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CollectionStatsView {
+    pub minted: U128,
+    pub revenue: U128,
+    // Block height of the last update
+    pub updated_at: U64,
+}
+
+impl From<CollectionStats> for CollectionStatsView {
+    fn from(stats: CollectionStats) -> Self {
+        CollectionStatsView {
+            minted: U128(stats.minted),
+            revenue: U128(stats.revenue),
+            updated_at: U64(stats.updated_at),
+        }
+    }
+}
+
+#[ext_contract(ext_nft_stats)]
+pub trait NftStats {
+    fn get_minted(&self) -> U128;
+}
+
+#[ext_contract(ext_self)]
+pub trait StatsCallbacks {
+    fn on_refresh_stats(&mut self, collection_id: U128) -> Option<U128>;
+}
+
+#[near_bindgen]
+impl INO {
+    // Called by the contract of an approved collection after each mint, with its new minted count
+    // and what the mint was paid
+    pub fn on_nft_minted(&mut self, minted: U128, price: U128) {
+        let collection_id = self
+            .collection_id_by_contract
+            .get(&env::predecessor_account_id())
+            .unwrap_or_else(|| ContractError::InvalidCollectionContract.panic());
+
+        let mut stats = self.internal_stats(collection_id);
+        stats.revenue += price.0;
+        self.internal_update_minted(collection_id, stats, minted.0);
+    }
+
+    // Anyone can pull the minted count from the linked contract, e.g. when reports were missed
+    pub fn refresh_stats(&mut self, collection_id: U128) -> Promise {
+        let collection = self
+            .collection_by_id
+            .get(&collection_id.0)
+            .unwrap_or_else(|| ContractError::InvalidCollectionId.panic());
+
+        if !self.status_by_id.get(&collection_id.0).map_or(false, CollectionStatus::is_approved) {
+            ContractError::CollectionNotApproved.panic();
+        }
+
+        ext_nft_stats::get_minted(&collection.contract, NO_DEPOSIT, GAS_FOR_GET_MINTED).then(
+            ext_self::on_refresh_stats(
+                collection_id,
+                &env::current_account_id(),
+                NO_DEPOSIT,
+                GAS_FOR_ON_REFRESH_STATS,
+            ),
+        )
+    }
+
+    #[private]
+    pub fn on_refresh_stats(&mut self, collection_id: U128) -> Option<U128> {
+        let minted = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value).ok()?,
+            _ => return None,
+        };
+
+        let stats = self.internal_stats(collection_id.0);
+        self.internal_update_minted(collection_id.0, stats, minted.0);
+
+        Some(minted)
+    }
+
+    pub fn get_collection_stats(&self, collection_id: U128) -> CollectionStatsView {
+        self.internal_stats(collection_id.0).into()
+    }
+}
+
+impl INO {
+    pub(crate) fn internal_stats(&self, collection_id: u128) -> CollectionStats {
+        self.stats_by_collection.get(&collection_id).unwrap_or_default()
+    }
+
+    // Reports can arrive out of order, so the minted count only moves forward
+    fn internal_update_minted(&mut self, collection_id: u128, mut stats: CollectionStats, minted: u128) {
+        stats.minted = stats.minted.max(minted);
+        stats.updated_at = env::block_index();
+        self.stats_by_collection.insert(&collection_id, &stats);
+
+        let collection = self.collection_by_id.get(&collection_id).unwrap();
+        if stats.minted >= collection.total_count
+            && self.status_by_id.get(&collection_id) == Some(CollectionStatus::Approved)
+        {
+            self.status_by_id.insert(&collection_id, &CollectionStatus::SoldOut);
+
+            log_event("collection_sold_out", json!({ "collection_id": U128(collection_id) }));
+        }
+    }
+}
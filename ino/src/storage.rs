@@ -0,0 +1,171 @@
+use crate::*;
+use crate::events::log_event;
+use ino_common::storage::{assert_unregister_not_forced, storage_deposit_kept};
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::assert_one_yocto;
+use near_sdk::serde_json::json;
+
+// Fixed part of a creator's registration entry: record overhead, map prefix and the stored balance
+const ACCOUNT_STORAGE_OVERHEAD_BYTES: u64 = 40 + 16 + 16 + 8;
+// Longest valid account id
+const MAX_ACCOUNT_ID_LENGTH: u64 = 64;
+// Records written next to a collection besides its own value:
+// status entry with its index and key vectors, and the creator entry
+const COLLECTION_FIXED_STORAGE_BYTES: u64 = 5 * 40 + 5 * 32 + 3 * 16 + 8;
+// Proposal bond record kept while the DAO holds the bond: overhead, prefixed id key, submitter and amount
+const PROPOSAL_BOND_STORAGE_BYTES: u64 = 40 + 27 + 16 + 4 + MAX_ACCOUNT_ID_LENGTH + 16;
+
+#[near_bindgen]
+impl StorageManagement for INO {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id: AccountId = account_id
+            .map(|account_id| account_id.into())
+            .unwrap_or_else(env::predecessor_account_id);
+
+        let deposit = storage_deposit_kept(
+            amount,
+            self.storage_accounts.get(&account_id).is_some(),
+            registration_only.unwrap_or(false),
+            self.storage_balance_bounds().min.0,
+        );
+
+        let account = match self.storage_accounts.get(&account_id) {
+            Some(mut account) => {
+                account.deposit += deposit;
+                account
+            },
+            None => StorageAccount { deposit, used_bytes: Self::registration_bytes() },
+        };
+
+        self.storage_accounts.insert(&account_id, &account);
+
+        log_event("storage_deposit", json!({ "account_id": account_id, "amount": U128(amount) }));
+
+        account.to_storage_balance()
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let mut account = self.internal_storage_account(&account_id);
+
+        let amount = account.withdraw(amount);
+        self.storage_accounts.insert(&account_id, &account);
+
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+
+        log_event("storage_withdraw", json!({ "account_id": account_id, "amount": U128(amount) }));
+
+        account.to_storage_balance()
+    }
+
+    // Collections can't be dropped on behalf of their creator, so `force` is not supported and
+    // accounts can only leave once all their pending collections are removed
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        assert_unregister_not_forced(force);
+
+        let account_id = env::predecessor_account_id();
+        let account = match self.storage_accounts.get(&account_id) {
+            None => return false,
+            Some(account) => account,
+        };
+
+        account.assert_unregisterable(Self::registration_bytes());
+
+        self.storage_accounts.remove(&account_id);
+        Promise::new(account_id.clone()).transfer(account.deposit);
+
+        log_event("storage_unregister", json!({ "account_id": account_id }));
+
+        true
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(Balance::from(Self::registration_bytes()) * env::storage_byte_cost()),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.storage_accounts
+            .get(account_id.as_ref())
+            .map(|account| account.to_storage_balance())
+    }
+}
+
+#[near_bindgen]
+impl INO {
+    // Upper estimate of what `add_collection` will take from the creator's storage balance
+    pub fn estimate_collection_storage(&self, collection: Collection) -> U128 {
+        U128(Balance::from(self.collection_storage_bytes(&StoredCollection::from(&collection))) * env::storage_byte_cost())
+    }
+}
+
+impl INO {
+    fn registration_bytes() -> u64 {
+        ACCOUNT_STORAGE_OVERHEAD_BYTES + MAX_ACCOUNT_ID_LENGTH
+    }
+
+    fn collection_storage_bytes(&self, collection: &StoredCollection) -> u64 {
+        let value_bytes = collection.try_to_vec().unwrap().len() as u64;
+        let proposal_bond_bytes = if self.proposal_bond() > 0 { PROPOSAL_BOND_STORAGE_BYTES } else { 0 };
+
+        // collection record: overhead, prefixed id key and value
+        40 + b"collection_by_id".len() as u64 + 16 + value_bytes
+            + COLLECTION_FIXED_STORAGE_BYTES
+            + MAX_ACCOUNT_ID_LENGTH
+            + proposal_bond_bytes
+    }
+
+    pub(crate) fn internal_storage_account(&self, account_id: &AccountId) -> StorageAccount {
+        self.storage_accounts
+            .get(account_id)
+            .unwrap_or_else(|| ContractError::StorageNotRegistered.panic_with(account_id))
+    }
+
+    // Credits a deposit attached to another call, registering the account first if it has to
+    pub(crate) fn internal_deposit_for_storage(&mut self, account_id: &AccountId, amount: Balance) {
+        let account = match self.storage_accounts.get(account_id) {
+            Some(mut account) => {
+                account.deposit += amount;
+                account
+            },
+            None => {
+                let min_balance = self.storage_balance_bounds().min.0;
+                if amount < min_balance {
+                    ContractError::StorageNotRegistered.panic_with(format!("attach at least {} yoctoNEAR to register", min_balance));
+                }
+                StorageAccount { deposit: amount, used_bytes: Self::registration_bytes() }
+            },
+        };
+
+        self.storage_accounts.insert(account_id, &account);
+    }
+
+    pub(crate) fn internal_charge_storage(&mut self, account_id: &AccountId, bytes: u64) {
+        let mut account = self.internal_storage_account(account_id);
+        account.charge(bytes, false);
+        self.storage_accounts.insert(account_id, &account);
+    }
+
+    pub(crate) fn internal_release_storage(&mut self, account_id: &AccountId, bytes: u64) {
+        let mut account = self.internal_storage_account(account_id);
+        account.release(bytes, Self::registration_bytes());
+        self.storage_accounts.insert(account_id, &account);
+    }
+}
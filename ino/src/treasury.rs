@@ -0,0 +1,30 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[near_bindgen]
+impl INO {
+    // Sends slashed bonds out of the contract, escrowed bonds and storage deposits stay untouched
+    pub fn withdraw(&mut self, amount: U128, receiver_id: Option<ValidAccountId>) -> Promise {
+        self.assert_role(Role::Treasurer);
+
+        let amount: Balance = amount.into();
+        let receiver_id: AccountId = match receiver_id {
+            None => env::predecessor_account_id(),
+            Some(account_id) => account_id.into(),
+        };
+
+        if amount > self.treasury_balance {
+            ContractError::WithdrawTooLarge.panic_with(format!("{} yoctoNEAR available", self.treasury_balance));
+        }
+        self.treasury_balance -= amount;
+
+        log_event("withdraw", json!({ "receiver_id": receiver_id, "amount": U128(amount), "by": env::predecessor_account_id() }));
+
+        Promise::new(receiver_id).transfer(amount)
+    }
+
+    pub fn get_treasury_balance(&self) -> U128 {
+        U128(self.treasury_balance)
+    }
+}
@@ -0,0 +1,250 @@
+use crate::*;
+use crate::events::log_event;
+use ino_common::state;
+use near_sdk::serde_json::json;
+use near_sdk::Gas;
+
+// Bumped with every change to the `INO` layout. Each released layout keeps a struct and a
+// decoder below so that `migrate` works from any of them.
+pub(crate) const CURRENT_STATE_VERSION: u16 = 3;
+
+// Gas kept back by `upgrade` itself, everything else is handed to `migrate`
+const GAS_FOR_UPGRADE: Gas = 10_000_000_000_000;
+
+// Layout of `INO` deployed before state versioning existed
+#[derive(BorshDeserialize)]
+pub(crate) struct InoV1 {
+    owner: AccountId,
+    collection_count: u128,
+    collection_by_id: LookupMap<u128, StoredCollection>,
+    // written as `bool`, which decodes as `Pending` or `Approved`
+    status_by_id: UnorderedMap<u128, CollectionStatus>,
+}
+
+// Layout of `INO` at state version 2, when pausing, roles, two-step ownership and DAO approval were added
+#[derive(BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub(crate) struct InoV2 {
+    owner: AccountId,
+    collection_count: u128,
+    collection_by_id: LookupMap<u128, StoredCollection>,
+    // written as `bool` too
+    status_by_id: UnorderedMap<u128, CollectionStatus>,
+    submissions_paused: bool,
+    roles_by_account: UnorderedMap<AccountId, Vec<Role>>,
+    pending_owner: Option<AccountId>,
+    dao: Option<StoredDaoConfig>,
+    proposal_id_by_collection: LookupMap<u128, u64>,
+}
+
+impl From<InoV1> for InoV2 {
+    fn from(old: InoV1) -> Self {
+        Self {
+            owner: old.owner,
+            collection_count: old.collection_count,
+            collection_by_id: old.collection_by_id,
+            status_by_id: old.status_by_id,
+            submissions_paused: false,
+            roles_by_account: UnorderedMap::new(b"roles_by_account".to_vec()),
+            pending_owner: None,
+            dao: None,
+            proposal_id_by_collection: LookupMap::new(b"proposal_id_by_collection".to_vec()),
+        }
+    }
+}
+
+pub(crate) enum VersionedIno {
+    V1(InoV1),
+    V2(InoV2),
+    Current(INO),
+}
+
+impl VersionedIno {
+    pub(crate) fn read() -> Self {
+        let (version, data) = state::read_state();
+
+        match version {
+            1 => VersionedIno::V1(state::decode_state::<InoV1>(&data, version)),
+            2 => VersionedIno::V2(state::decode_state::<InoV2>(&data, version)),
+            CURRENT_STATE_VERSION => VersionedIno::Current(state::decode_state::<INO>(&data, version)),
+            _ => ContractError::UnknownStateVersion.panic_with(version),
+        }
+    }
+
+    pub(crate) fn into_current(self) -> INO {
+        match self {
+            VersionedIno::V1(old) => VersionedIno::V2(old.into()).into_current(),
+            VersionedIno::V2(old) => {
+                let collection_id_by_contract = approved_collection_ids_by_contract(&old);
+
+                INO {
+                    owner: old.owner,
+                    collection_count: old.collection_count,
+                    collection_by_id: old.collection_by_id,
+                    status_by_id: old.status_by_id,
+                    submissions_paused: old.submissions_paused,
+                    roles_by_account: old.roles_by_account,
+                    pending_owner: old.pending_owner,
+                    dao: old.dao,
+                    proposal_id_by_collection: old.proposal_id_by_collection,
+                    storage_accounts: LookupMap::new(b"storage_accounts".to_vec()),
+                    creator_by_id: LookupMap::new(b"creator_by_id".to_vec()),
+                    submission_bond: 0,
+                    bond_by_collection: LookupMap::new(b"bond_by_collection".to_vec()),
+                    treasury_balance: 0,
+                    sold_by_collection: LookupMap::new(b"sold_by_collection".to_vec()),
+                    stats_by_collection: LookupMap::new(b"stats_by_collection".to_vec()),
+                    collection_id_by_contract,
+                    flag_reason_by_collection: LookupMap::new(b"flag_reason_by_collection".to_vec()),
+                    proposal_bond_by_collection: LookupMap::new(b"proposal_bond_by_collection".to_vec()),
+                }
+            },
+            VersionedIno::Current(ino) => ino,
+        }
+    }
+}
+
+// Links the contracts of collections approved before mint reports existed, so they report too.
+// When several share a contract the earliest keeps it.
+fn approved_collection_ids_by_contract(old: &InoV2) -> LookupMap<AccountId, u128> {
+    let mut collection_id_by_contract = LookupMap::new(b"collection_id_by_contract".to_vec());
+
+    for id in 0..old.collection_count {
+        if !old.status_by_id.get(&id).map_or(false, CollectionStatus::is_approved) {
+            continue;
+        }
+        if let Some(collection) = old.collection_by_id.get(&id) {
+            if !collection_id_by_contract.contains_key(&collection.contract) {
+                collection_id_by_contract.insert(&collection.contract, &id);
+            }
+        }
+    }
+
+    collection_id_by_contract
+}
+
+pub(crate) fn write_state_version() {
+    state::write_state_version(CURRENT_STATE_VERSION);
+}
+
+#[near_bindgen]
+impl INO {
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let contract = VersionedIno::read().into_current();
+        write_state_version();
+
+        log_event("state_migrated", json!({ "version": CURRENT_STATE_VERSION }));
+
+        contract
+    }
+
+    // Deploys the wasm passed as raw input over this account and runs `migrate` on it
+    pub fn upgrade(&self) -> Promise {
+        self.assert_owner();
+
+        state::upgrade_self(GAS_FOR_UPGRADE)
+    }
+
+    pub fn get_state_version(&self) -> u16 {
+        CURRENT_STATE_VERSION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    // V1 as it was written, with statuses stored as `bool`
+    #[derive(BorshSerialize)]
+    struct WrittenInoV1 {
+        owner: AccountId,
+        collection_count: u128,
+        collection_by_id: LookupMap<u128, StoredCollection>,
+        status_by_id: UnorderedMap<u128, bool>,
+    }
+
+    fn collection(name: &str) -> StoredCollection {
+        StoredCollection {
+            name: name.to_string(),
+            symbol: name.to_uppercase(),
+            url: format!("https://{}.io", name),
+            total_count: 10,
+            price: 1,
+            contract: format!("{}.testnet", name),
+        }
+    }
+
+    #[test]
+    fn migrate_v1_decodes_bool_statuses() {
+        testing_env!(VMContextBuilder::new().build());
+
+        let mut collection_by_id = LookupMap::new(b"collection_by_id".to_vec());
+        let mut status_by_id = UnorderedMap::new(b"status_by_id".to_vec());
+        collection_by_id.insert(&0, &collection("alpha"));
+        status_by_id.insert(&0, &true);
+        collection_by_id.insert(&1, &collection("beta"));
+        status_by_id.insert(&1, &false);
+
+        let owner: AccountId = accounts(0).into();
+        env::state_write(&WrittenInoV1 { owner: owner.clone(), collection_count: 2, collection_by_id, status_by_id });
+
+        let contract = INO::migrate();
+        assert_eq!(state::read_state().0, CURRENT_STATE_VERSION);
+        assert_eq!(contract.get_owner(), owner);
+
+        let collections = contract.get_collections(None, None);
+        assert_eq!(collections.len(), 2);
+        assert_eq!(collections[0].collection.name, "alpha");
+        assert_eq!(collections[0].status, CollectionStatus::Approved);
+        assert_eq!(collections[1].status, CollectionStatus::Pending);
+
+        // the approved collection's contract can report its mints
+        assert_eq!(contract.collection_id_by_contract.get(&"alpha.testnet".to_string()), Some(0));
+        assert_eq!(contract.collection_id_by_contract.get(&"beta.testnet".to_string()), None);
+    }
+
+    #[test]
+    fn migrate_v2_keeps_dao_and_proposals() {
+        testing_env!(VMContextBuilder::new().build());
+
+        let mut collection_by_id = LookupMap::new(b"collection_by_id".to_vec());
+        let mut status_by_id = UnorderedMap::new(b"status_by_id".to_vec());
+        let mut proposal_id_by_collection = LookupMap::new(b"proposal_id_by_collection".to_vec());
+        collection_by_id.insert(&0, &collection("alpha"));
+        status_by_id.insert(&0, &CollectionStatus::Pending);
+        proposal_id_by_collection.insert(&0, &7);
+
+        env::state_write(&InoV2 {
+            owner: accounts(0).into(),
+            collection_count: 1,
+            collection_by_id,
+            status_by_id,
+            submissions_paused: true,
+            roles_by_account: UnorderedMap::new(b"roles_by_account".to_vec()),
+            pending_owner: None,
+            dao: Some(StoredDaoConfig { account_id: accounts(3).into(), proposal_bond: 1, auto_propose: true }),
+            proposal_id_by_collection,
+        });
+        state::write_state_version(2);
+
+        let contract = INO::migrate();
+        assert_eq!(state::read_state().0, CURRENT_STATE_VERSION);
+        assert!(contract.get_pause_status().submissions_paused);
+        assert_eq!(contract.dao.as_ref().map(|dao| dao.account_id.clone()), Some(accounts(3).into()));
+        assert_eq!(contract.proposal_id_by_collection.get(&0), Some(7));
+        assert_eq!(contract.get_collections(None, None)[0].status, CollectionStatus::Pending);
+    }
+
+    #[test]
+    #[should_panic(expected = "E602")]
+    fn migrate_rejects_undecodable_state() {
+        testing_env!(VMContextBuilder::new().build());
+        env::storage_write(b"STATE", &[1, 2, 3]);
+
+        INO::migrate();
+    }
+}
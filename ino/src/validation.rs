@@ -0,0 +1,44 @@
+use crate::*;
+
+const MAX_NAME_LENGTH: usize = 64;
+const MAX_SYMBOL_LENGTH: usize = 16;
+const MAX_URL_LENGTH: usize = 256;
+const ALLOWED_URL_SCHEMES: [&str; 2] = ["https://", "ipfs://"];
+
+impl Collection {
+    // Checked on submission so the registry only holds well-formed, bounded entries
+    pub(crate) fn assert_valid(&self) {
+        let name = self.name.trim();
+        if name.is_empty() || self.name.len() > MAX_NAME_LENGTH {
+            ContractError::InvalidCollectionName.panic_with(format!("1 to {} bytes", MAX_NAME_LENGTH));
+        }
+
+        if self.symbol.is_empty()
+            || self.symbol.len() > MAX_SYMBOL_LENGTH
+            || !self.symbol.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            ContractError::InvalidCollectionSymbol
+                .panic_with(format!("1 to {} ascii letters or digits", MAX_SYMBOL_LENGTH));
+        }
+
+        let has_allowed_scheme = ALLOWED_URL_SCHEMES
+            .iter()
+            .any(|scheme| self.url.starts_with(scheme) && self.url.len() > scheme.len());
+        if !has_allowed_scheme || self.url.len() > MAX_URL_LENGTH || self.url.contains(char::is_whitespace) {
+            ContractError::InvalidCollectionUrl
+                .panic_with(format!("{} url up to {} bytes", ALLOWED_URL_SCHEMES.join(" or "), MAX_URL_LENGTH));
+        }
+
+        if self.total_count.0 == 0 {
+            ContractError::InvalidCollectionTotalCount.panic();
+        }
+
+        if self.price.0 == 0 {
+            ContractError::InvalidCollectionPrice.panic();
+        }
+
+        if !env::is_valid_account_id(self.contract.as_bytes()) {
+            ContractError::InvalidCollectionContract.panic_with(&self.contract);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::*;
+use crate::storage::bytes_for_approved_account_id;
+use near_contract_standards::non_fungible_token::approval::NonFungibleTokenApproval;
+use near_sdk::{assert_one_yocto, ext_contract, Gas};
+
+const GAS_FOR_NFT_APPROVE: Gas = 10_000_000_000_000;
+
+#[ext_contract(ext_approval_receiver)]
+pub trait NonFungibleTokenApprovalReceiver {
+    fn nft_on_approve(&mut self, token_id: TokenId, owner_id: AccountId, approval_id: u64, msg: String);
+}
+
+// Approval methods are written out instead of using `impl_non_fungible_token_approval!`
+// so that approvals are paid from the owner's storage balance rather than an attached deposit.
+#[near_bindgen]
+impl NonFungibleTokenApproval for Contract {
+    #[payable]
+    fn nft_approve(
+        &mut self,
+        token_id: TokenId,
+        account_id: ValidAccountId,
+        msg: Option<String>,
+    ) -> Option<Promise> {
+        assert_one_yocto();
+
+        let owner_id = self.internal_assert_token_owner(&token_id);
+        let account_id: AccountId = account_id.into();
+
+        let approvals_by_id = self.tokens.approvals_by_id.as_mut().unwrap();
+        let next_approval_id_by_id = self.tokens.next_approval_id_by_id.as_mut().unwrap();
+
+        let mut approved_account_ids = approvals_by_id.get(&token_id).unwrap_or_default();
+        let approval_id: u64 = next_approval_id_by_id.get(&token_id).unwrap_or(1);
+        let old_approval_id = approved_account_ids.insert(account_id.clone(), approval_id);
+
+        approvals_by_id.insert(&token_id, &approved_account_ids);
+        next_approval_id_by_id.insert(&token_id, &(approval_id + 1));
+
+        if old_approval_id.is_none() {
+            self.internal_charge_storage(&owner_id, bytes_for_approved_account_id(&account_id), false);
+        }
+
+        msg.map(|msg| {
+            ext_approval_receiver::nft_on_approve(
+                token_id,
+                owner_id,
+                approval_id,
+                msg,
+                &account_id,
+                NO_DEPOSIT,
+                env::prepaid_gas() - GAS_FOR_NFT_APPROVE,
+            )
+        })
+    }
+
+    #[payable]
+    fn nft_revoke(&mut self, token_id: TokenId, account_id: ValidAccountId) {
+        assert_one_yocto();
+
+        let owner_id = self.internal_assert_token_owner(&token_id);
+
+        let approvals_by_id = self.tokens.approvals_by_id.as_mut().unwrap();
+        if let Some(mut approved_account_ids) = approvals_by_id.get(&token_id) {
+            if approved_account_ids.remove(account_id.as_ref()).is_some() {
+                if approved_account_ids.is_empty() {
+                    approvals_by_id.remove(&token_id);
+                } else {
+                    approvals_by_id.insert(&token_id, &approved_account_ids);
+                }
+
+                self.internal_release_storage(&owner_id, bytes_for_approved_account_id(account_id.as_ref()));
+            }
+        }
+    }
+
+    #[payable]
+    fn nft_revoke_all(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+
+        let owner_id = self.internal_assert_token_owner(&token_id);
+
+        let approvals_by_id = self.tokens.approvals_by_id.as_mut().unwrap();
+        if let Some(approved_account_ids) = approvals_by_id.remove(&token_id) {
+            self.internal_release_storage(
+                &owner_id,
+                storage::approvals_storage_bytes(Some(&approved_account_ids)),
+            );
+        }
+    }
+
+    fn nft_is_approved(
+        self,
+        token_id: TokenId,
+        approved_account_id: ValidAccountId,
+        approval_id: Option<u64>,
+    ) -> bool {
+        self.tokens.nft_is_approved(token_id, approved_account_id, approval_id)
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_assert_token_owner(&self, token_id: &TokenId) -> AccountId {
+        let owner_id = self
+            .tokens
+            .owner_by_id
+            .get(token_id)
+            .unwrap_or_else(|| ContractError::TokenNotFound.panic_with(token_id));
+
+        if env::predecessor_account_id() != owner_id {
+            ContractError::NotTokenOwner.panic();
+        }
+
+        owner_id
+    }
+}
@@ -0,0 +1,145 @@
+use crate::*;
+
+const MAX_ATTRIBUTE_LENGTH: usize = 64;
+// Rarity scores are returned in thousandths to stay integer
+const RARITY_SCALE: u128 = 1_000;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenAttribute {
+    pub trait_type: String,
+    pub value: String,
+}
+
+impl TokenAttribute {
+    pub(crate) fn assert_valid(&self) {
+        if self.trait_type.is_empty() || self.value.is_empty() {
+            ContractError::InvalidAttribute.panic_with("trait type and value should not be empty");
+        }
+        if self.trait_type.len() > MAX_ATTRIBUTE_LENGTH || self.value.len() > MAX_ATTRIBUTE_LENGTH {
+            ContractError::InvalidAttribute.panic_with(format!("trait type and value are limited to {} bytes", MAX_ATTRIBUTE_LENGTH));
+        }
+    }
+
+    fn index_key(&self) -> CryptoHash {
+        env::sha256(&(self.trait_type.clone(), self.value.clone()).try_to_vec().unwrap())
+            .try_into()
+            .unwrap()
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Lists minted tokens carrying the given trait, ids that are not minted yet are skipped
+    pub fn nft_tokens_by_trait(
+        &self,
+        trait_type: String,
+        value: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<Token> {
+        let attribute = TokenAttribute { trait_type, value };
+        let token_ids = match self.tokens_by_trait.get(&attribute.index_key()) {
+            None => return vec![],
+            Some(token_ids) => token_ids,
+        };
+
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        token_ids
+            .iter()
+            .filter(|id| self.internal_is_minted(*id))
+            .skip(start as usize)
+            .take(limit.unwrap_or(u64::MAX) as usize)
+            .filter_map(|id| self.internal_token(id.to_string()))
+            .collect()
+    }
+
+    // Number of ids per trait value, over the whole uploaded collection
+    pub fn get_trait_counts(&self, trait_type: Option<String>) -> HashMap<String, HashMap<String, U64>> {
+        let counts: HashMap<String, HashMap<String, u64>> = match trait_type {
+            Some(trait_type) => self
+                .trait_counts
+                .get(&trait_type)
+                .map(|counts| vec![(trait_type, counts)])
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            None => self.trait_counts.iter().collect(),
+        };
+
+        counts
+            .into_iter()
+            .map(|(trait_type, values)| {
+                (trait_type, values.into_iter().map(|(value, count)| (value, U64(count))).collect())
+            })
+            .collect()
+    }
+
+    // Sum over the token's traits of `total_count / ids sharing the value`, in thousandths
+    pub fn get_rarity_score(&self, token_id: TokenId) -> Option<U128> {
+        let id = token_id.parse::<u128>().ok()?;
+        let upload = self.metadata_uploads.get(&id)?;
+
+        let score: u128 = upload
+            .attributes
+            .iter()
+            .filter_map(|attribute| {
+                let count = *self.trait_counts.get(&attribute.trait_type)?.get(&attribute.value)?;
+                Some(self.total_count * RARITY_SCALE / u128::from(count))
+            })
+            .sum();
+
+        Some(U128(score))
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_add_attributes(&mut self, token_id: u128, attributes: &[TokenAttribute]) {
+        for attribute in attributes {
+            let key = attribute.index_key();
+            let mut token_ids = self.tokens_by_trait.get(&key).unwrap_or_else(|| {
+                UnorderedSet::new(StorageKey::TokensByTraitInner { trait_hash: key })
+            });
+            if !token_ids.insert(&token_id) {
+                continue;
+            }
+            self.tokens_by_trait.insert(&key, &token_ids);
+
+            let mut counts = self.trait_counts.get(&attribute.trait_type).unwrap_or_default();
+            *counts.entry(attribute.value.clone()).or_insert(0) += 1;
+            self.trait_counts.insert(&attribute.trait_type, &counts);
+        }
+    }
+
+    pub(crate) fn internal_remove_attributes(&mut self, token_id: u128, attributes: &[TokenAttribute]) {
+        for attribute in attributes {
+            let key = attribute.index_key();
+            let mut token_ids = match self.tokens_by_trait.get(&key) {
+                None => continue,
+                Some(token_ids) => token_ids,
+            };
+            if !token_ids.remove(&token_id) {
+                continue;
+            }
+            if token_ids.is_empty() {
+                self.tokens_by_trait.remove(&key);
+            } else {
+                self.tokens_by_trait.insert(&key, &token_ids);
+            }
+
+            let mut counts = self.trait_counts.get(&attribute.trait_type).unwrap_or_default();
+            if let Some(count) = counts.get_mut(&attribute.value) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&attribute.value);
+                }
+            }
+            if counts.is_empty() {
+                self.trait_counts.remove(&attribute.trait_type);
+            } else {
+                self.trait_counts.insert(&attribute.trait_type, &counts);
+            }
+        }
+    }
+}
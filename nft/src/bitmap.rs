@@ -0,0 +1,97 @@
+use crate::*;
+use near_sdk::IntoStorageKey;
+
+pub const BITS_PER_WORD: u128 = 128;
+
+// Minted ids packed 128 per storage entry, word `n` holds ids `n * 128..(n + 1) * 128`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MintedBitmap {
+    words: LookupMap<u64, u128>,
+}
+
+impl MintedBitmap {
+    pub fn new<S: IntoStorageKey>(prefix: S) -> Self {
+        Self { words: LookupMap::new(prefix) }
+    }
+
+    pub fn word(&self, index: u64) -> u128 {
+        self.words.get(&index).unwrap_or(0)
+    }
+
+    pub fn contains(&self, id: u128) -> bool {
+        self.word(word_index(id)) & bit_mask(id) != 0
+    }
+
+    pub fn insert(&mut self, id: u128) {
+        let index = word_index(id);
+        let word = self.word(index) | bit_mask(id);
+
+        self.words.insert(&index, &word);
+    }
+}
+
+fn word_index(id: u128) -> u64 {
+    (id / BITS_PER_WORD) as u64
+}
+
+fn bit_mask(id: u128) -> u128 {
+    1u128 << (id % BITS_PER_WORD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    #[test]
+    fn insert_sets_only_its_own_bit() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut bitmap = MintedBitmap::new(b"m".to_vec());
+
+        for id in &[0, 5, 127, 128, 300] {
+            bitmap.insert(*id);
+        }
+
+        for id in 0..400u128 {
+            assert_eq!(bitmap.contains(id), [0, 5, 127, 128, 300].contains(&id), "id {}", id);
+        }
+    }
+
+    #[test]
+    fn words_hold_128_ids_each() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut bitmap = MintedBitmap::new(b"m".to_vec());
+
+        bitmap.insert(1);
+        bitmap.insert(127);
+        bitmap.insert(129);
+
+        assert_eq!(bitmap.word(0), (1u128 << 1) | (1u128 << 127));
+        assert_eq!(bitmap.word(1), 1u128 << 1);
+        assert_eq!(bitmap.word(2), 0);
+    }
+
+    #[test]
+    fn insert_is_idempotent() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut bitmap = MintedBitmap::new(b"m".to_vec());
+
+        bitmap.insert(42);
+        bitmap.insert(42);
+
+        assert_eq!(bitmap.word(0), 1u128 << 42);
+    }
+
+    #[test]
+    fn handles_ids_past_u64() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut bitmap = MintedBitmap::new(b"m".to_vec());
+        let id = u128::from(u64::MAX) * 2;
+
+        bitmap.insert(id);
+
+        assert!(bitmap.contains(id));
+        assert!(!bitmap.contains(id - 1));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+// Ids hashed per `freeze_metadata` call unless asked otherwise
+const DEFAULT_FREEZE_BATCH: u64 = 100;
+
+// Recorded when metadata is frozen so buyers can check nothing changed afterwards
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataProvenance {
+    pub block_height: U64,
+    pub metadata_hash: Base64VecU8,
+}
+
+// How `MetadataProvenance` is kept in state
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct StoredMetadataProvenance {
+    pub block_height: u64,
+    pub metadata_hash: CryptoHash,
+}
+
+impl From<&StoredMetadataProvenance> for MetadataProvenance {
+    fn from(provenance: &StoredMetadataProvenance) -> Self {
+        Self {
+            block_height: U64(provenance.block_height),
+            metadata_hash: Base64VecU8(provenance.metadata_hash.to_vec()),
+        }
+    }
+}
+
+// Progress of a freeze spread over several calls: ids below `next_id` are folded into `hash`
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MetadataFreeze {
+    pub next_id: u128,
+    pub hash: CryptoHash,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_contract_metadata(&mut self, metadata: NFTContractMetadata) {
+        self.assert_owner();
+        self.assert_metadata_not_frozen();
+
+        metadata.assert_valid();
+        self.metadata.set(&metadata);
+
+        log_event("contract_metadata_updated", json!({ "metadata": metadata }));
+    }
+
+    // Minted tokens link relative to the base uri, so they all follow it
+    pub fn set_base_uri(&mut self, base_uri: Option<String>) {
+        self.assert_owner();
+        self.assert_metadata_not_frozen();
+
+        let mut metadata = self.metadata.get().unwrap();
+        metadata.base_uri = base_uri;
+        metadata.assert_valid();
+        self.metadata.set(&metadata);
+
+        log_event("contract_metadata_updated", json!({ "metadata": metadata }));
+    }
+
+    // Irreversible, meant to be called once the collection is finalized. Locks the collection
+    // metadata, the template and per-token uploads, and records a hash of every token's metadata.
+    // Hashes up to `limit` ids per call, metadata is locked from the first call and the freeze is
+    // recorded by the call hashing the last id. Returns whether that happened.
+    pub fn freeze_metadata(&mut self, limit: Option<U64>) -> bool {
+        self.assert_owner();
+        if self.metadata_provenance.is_some() {
+            ContractError::MetadataFrozen.panic();
+        }
+
+        let mut freeze = self.metadata_freeze.take().unwrap_or(MetadataFreeze { next_id: 0, hash: [0u8; 32] });
+        let end = self
+            .total_count
+            .min(freeze.next_id + u128::from(limit.map_or(DEFAULT_FREEZE_BATCH, |limit| limit.0)));
+        freeze.hash = self.internal_chain_token_metadata_hash(freeze.hash, freeze.next_id, end);
+        freeze.next_id = end;
+
+        if freeze.next_id < self.total_count {
+            log_event("metadata_freeze_progress", json!({ "hashed": U128(freeze.next_id), "total": U128(self.total_count) }));
+            self.metadata_freeze = Some(freeze);
+            return false;
+        }
+
+        self.metadata_provenance = Some(StoredMetadataProvenance {
+            block_height: env::block_index(),
+            metadata_hash: freeze.hash,
+        });
+
+        log_event("metadata_frozen", json!({
+            "provenance": self.get_metadata_provenance(),
+            "by": env::predecessor_account_id(),
+        }));
+
+        true
+    }
+
+    pub fn is_metadata_frozen(&self) -> bool {
+        self.metadata_provenance.is_some()
+    }
+
+    pub fn get_metadata_provenance(&self) -> Option<MetadataProvenance> {
+        self.metadata_provenance.as_ref().map(MetadataProvenance::from)
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_metadata_not_frozen(&self) {
+        if self.metadata_provenance.is_some() {
+            ContractError::MetadataFrozen.panic();
+        }
+        if self.metadata_freeze.is_some() {
+            ContractError::MetadataFrozen.panic_with("a freeze is in progress");
+        }
+    }
+
+    // Chained over ids in order: hash = sha256(hash || sha256(borsh(metadata))), starting from 32 zero bytes.
+    // Minted tokens use their stored metadata, the rest the metadata `nft_mint` would give them.
+    // Folds ids `from..to` into `hash`.
+    fn internal_chain_token_metadata_hash(&self, mut hash: CryptoHash, from: u128, to: u128) -> CryptoHash {
+        let contract_metadata = self.metadata.get().unwrap();
+
+        for id in from..to {
+            let stored = self
+                .tokens
+                .token_metadata_by_id
+                .as_ref()
+                .and_then(|by_id| by_id.get(&id.to_string()));
+            let token_metadata = stored.unwrap_or_else(|| self.internal_token_metadata(&contract_metadata, id));
+
+            let mut chained = hash.to_vec();
+            chained.extend(env::sha256(&token_metadata.try_to_vec().unwrap()));
+            hash = env::sha256(&chained).try_into().unwrap();
+        }
+
+        hash
+    }
+}
@@ -0,0 +1,8 @@
+use near_sdk::serde_json::Value;
+
+const EVENT_STANDARD: &str = "ino_nft";
+const EVENT_VERSION: &str = "1.0.0";
+
+pub(crate) fn log_event(event: &str, data: Value) {
+    ino_common::log_event(EVENT_STANDARD, EVENT_VERSION, event, data);
+}
@@ -0,0 +1,45 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+use near_sdk::{ext_contract, Gas};
+
+const GAS_FOR_MINT_REPORT: Gas = 5_000_000_000_000;
+
+#[ext_contract(ext_launchpad)]
+pub trait Launchpad {
+    fn on_nft_minted(&mut self, minted: U128, price: U128);
+}
+
+#[near_bindgen]
+impl Contract {
+    // INO registry told about every mint so its sales stats stay current. It can't change while
+    // vesting is frozen: it is the only account able to lift the freeze, so the owner could
+    // otherwise point it at themselves and lift it. Freezing requires one, so it is always set then.
+    pub fn set_launchpad(&mut self, account_id: Option<ValidAccountId>) {
+        self.assert_owner();
+        self.assert_vesting_not_frozen();
+
+        self.launchpad = account_id.map(|account_id| account_id.into());
+
+        log_event("launchpad_updated", json!({ "account_id": self.launchpad }));
+    }
+
+    pub fn get_launchpad(&self) -> Option<AccountId> {
+        self.launchpad.clone()
+    }
+}
+
+impl Contract {
+    // Fire and forget, a failed report must not undo the mint
+    pub(crate) fn internal_report_mint(&self, price: Balance) {
+        if let Some(launchpad) = &self.launchpad {
+            ext_launchpad::on_nft_minted(
+                U128(self.minted_count),
+                U128(price),
+                launchpad,
+                0,
+                GAS_FOR_MINT_REPORT,
+            );
+        }
+    }
+}
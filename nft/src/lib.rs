@@ -1,25 +1,97 @@
 use std::convert::TryFrom;
 use std::convert::TryInto;
-use rand::rngs::StdRng;
-use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
 
 use near_contract_standards::non_fungible_token::metadata::{
     NFTContractMetadata, NonFungibleTokenMetadataProvider, TokenMetadata, NFT_METADATA_SPEC,
 };
 use near_contract_standards::non_fungible_token::{Token, TokenId};
 use near_contract_standards::non_fungible_token::NonFungibleToken;
+use near_contract_standards::non_fungible_token::core::{NonFungibleTokenCore, NonFungibleTokenResolver};
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{UnorderedMap, LazyOption, UnorderedSet};
+use near_sdk::collections::{UnorderedMap, LazyOption, LookupMap, UnorderedSet};
 use near_sdk::json_types::*;
 use near_sdk::{
-    env, near_bindgen, AccountId, Balance, BorshStorageKey, PanicOnDefault, Promise, PromiseOrValue, CryptoHash,
+    assert_one_yocto, env, ext_contract, near_bindgen, AccountId, Balance, BorshStorageKey, CryptoHash, Gas,
+    PanicOnDefault, Promise, PromiseOrValue, PromiseResult,
 };
 use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::serde_json::json;
+use ino_common::{AccessControl, ContractError, StorageAccount};
+
+mod approval;
+mod attributes;
+mod bitmap;
+mod contract_metadata;
+mod events;
+mod launchpad;
+mod mint_gate;
+mod ownership;
+mod pause;
+mod raffle;
+mod roles;
+mod sale_state;
+pub mod selection;
+mod soft_cap;
+mod storage;
+mod template;
+mod token_metadata;
+mod treasury;
+mod upgrade;
+mod vesting;
+
+pub use crate::attributes::TokenAttribute;
+pub use crate::contract_metadata::MetadataProvenance;
+use crate::contract_metadata::{MetadataFreeze, StoredMetadataProvenance};
+use crate::bitmap::MintedBitmap;
+use crate::events::log_event;
+pub use crate::mint_gate::MintGate;
+use crate::mint_gate::StoredMintGate;
+pub use crate::pause::PauseStatus;
+pub use crate::raffle::{RaffleEntryView, RaffleView};
+use crate::raffle::{Raffle, RaffleEntry};
+pub use crate::roles::Role;
+pub use crate::sale_state::{MintCountsView, SalePhase, SaleState};
+use crate::sale_state::{MintCounts, MintKind};
+pub use crate::soft_cap::SoftCapView;
+use crate::soft_cap::SoftCap;
+pub use crate::storage::MintCost;
+pub use crate::template::MetadataTemplate;
+pub use crate::token_metadata::{TokenMetadataProgress, TokenMetadataUpload};
+use crate::token_metadata::StoredTokenMetadata;
+pub use crate::vesting::VestingView;
+use crate::vesting::Vesting;
 
 near_sdk::setup_alloc!();
 
 const MULTIPLYER:Balance = 10_000_000_000_000_000;
 
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 5_000_000_000_000;
+const GAS_FOR_NFT_TRANSFER_CALL: Gas = 25_000_000_000_000 + GAS_FOR_RESOLVE_TRANSFER;
+const NO_DEPOSIT: Balance = 0;
+
+#[ext_contract(ext_receiver)]
+pub trait NonFungibleTokenReceiver {
+    fn nft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        previous_owner_id: AccountId,
+        token_id: TokenId,
+        msg: String,
+    ) -> PromiseOrValue<bool>;
+}
+
+#[ext_contract(ext_self)]
+pub trait NonFungibleTokenResolverExt {
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool;
+}
+
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
@@ -27,8 +99,39 @@ pub struct Contract {
     metadata: LazyOption<NFTContractMetadata>,
     total_count: u128,
     minted_count: u128,
-    is_minted_by_id: UnorderedMap<u128, bool>,
+    minted_ids: MintedBitmap,
     mint_price: Balance,
+    mint_paused: bool,
+    transfers_paused: bool,
+    reserved_minted_count: u128,
+    roles_by_account: UnorderedMap<AccountId, Vec<Role>>,
+    pending_owner: Option<AccountId>,
+    metadata_template: MetadataTemplate,
+    metadata_uploads: LookupMap<u128, StoredTokenMetadata>,
+    loaded_metadata_count: u128,
+    trait_counts: UnorderedMap<String, HashMap<String, u64>>,
+    tokens_by_trait: LookupMap<CryptoHash, UnorderedSet<u128>>,
+    metadata_provenance: Option<StoredMetadataProvenance>,
+    burned_count: u128,
+    mint_counts_by_account: LookupMap<AccountId, MintCounts>,
+    storage_accounts: LookupMap<AccountId, StorageAccount>,
+    storage_deposits: Balance,
+    storage_used_bytes: u64,
+    launchpad: Option<AccountId>,
+    soft_cap: Option<SoftCap>,
+    escrowed_proceeds: Balance,
+    paid_by_token: LookupMap<u128, Balance>,
+    released_proceeds: Balance,
+    vesting: Option<Vesting>,
+    raffle: Option<Raffle>,
+    raffle_entries: UnorderedMap<AccountId, RaffleEntry>,
+    mint_gate: Option<StoredMintGate>,
+    // V1 minted ids still waiting to be moved into `minted_ids`
+    legacy_minted_ids: Option<UnorderedMap<u128, bool>>,
+    metadata_freeze: Option<MetadataFreeze>,
+    storage_bytes_by_token: LookupMap<u128, u64>,
+    // Largest upload so far, bounds what an upload adds to a token's metadata
+    max_upload_bytes: u64,
 }
 
 #[derive(BorshSerialize, BorshStorageKey)]
@@ -40,16 +143,45 @@ enum StorageKey {
     Approval,
     TokensPerOwner { account_hash: Vec<u8> },
     TokenPerOwnerInner { account_id_hash: CryptoHash },
+    Roles,
+    MintedIds,
+    MetadataUploads,
+    TraitCounts,
+    TokensByTrait,
+    TokensByTraitInner { trait_hash: CryptoHash },
+    MintCountsByAccount,
+    StorageAccounts,
+    PaidByToken,
+    RaffleEntries,
+    StorageBytesByToken,
 }
 
 #[near_bindgen]
 impl Contract {
 
     #[init]
-    pub fn new(owner_id: ValidAccountId, metadata: NFTContractMetadata, price: Balance, count: u128) -> Self {
-        assert!(!env::state_exists(), "Already initialized");
+    pub fn new(
+        owner_id: ValidAccountId,
+        metadata: NFTContractMetadata,
+        price: U128,
+        count: U128,
+        template: Option<MetadataTemplate>,
+    ) -> Self {
+        if env::state_exists() {
+            ContractError::AlreadyInitialized.panic();
+        }
         
         metadata.assert_valid();
+
+        let price: Balance = price.into();
+        if price.checked_mul(MULTIPLYER).is_none() {
+            ContractError::PriceTooLarge.panic();
+        }
+
+        let template = template.unwrap_or_default();
+        template.assert_valid();
+
+        upgrade::write_state_version();
         
         Self {
             tokens: NonFungibleToken::new(
@@ -60,91 +192,342 @@ impl Contract {
                 Some(StorageKey::Approval),
             ),
             metadata: LazyOption::new(StorageKey::Metadata, Some(&metadata)),
-            total_count: count,
+            total_count: count.into(),
             minted_count: 0,
-            is_minted_by_id: UnorderedMap::new(b"is_minted_by_id".to_vec()),
-            mint_price: price
+            minted_ids: MintedBitmap::new(StorageKey::MintedIds),
+            mint_price: price,
+            mint_paused: false,
+            transfers_paused: false,
+            reserved_minted_count: 0,
+            roles_by_account: UnorderedMap::new(StorageKey::Roles),
+            pending_owner: None,
+            metadata_template: template,
+            metadata_uploads: LookupMap::new(StorageKey::MetadataUploads),
+            loaded_metadata_count: 0,
+            trait_counts: UnorderedMap::new(StorageKey::TraitCounts),
+            tokens_by_trait: LookupMap::new(StorageKey::TokensByTrait),
+            metadata_provenance: None,
+            burned_count: 0,
+            mint_counts_by_account: LookupMap::new(StorageKey::MintCountsByAccount),
+            storage_accounts: LookupMap::new(StorageKey::StorageAccounts),
+            storage_deposits: 0,
+            storage_used_bytes: 0,
+            launchpad: None,
+            soft_cap: None,
+            escrowed_proceeds: 0,
+            paid_by_token: LookupMap::new(StorageKey::PaidByToken),
+            released_proceeds: 0,
+            vesting: None,
+            raffle: None,
+            raffle_entries: UnorderedMap::new(StorageKey::RaffleEntries),
+            mint_gate: None,
+            legacy_minted_ids: None,
+            metadata_freeze: None,
+            storage_bytes_by_token: LookupMap::new(StorageKey::StorageBytesByToken),
+            max_upload_bytes: 0,
         }
     }
 
+    // With a mint gate set the token is minted in a callback once the receiver's holdings are
+    // checked, and the deposit is refunded if they fall short
     #[payable]
     pub fn nft_mint(
         &mut self,
         receiver_id: ValidAccountId,
-    ) -> Token {
+    ) -> PromiseOrValue<Token> {
+        self.assert_mint_not_paused();
 
         if env::attached_deposit() < self.mint_price * MULTIPLYER {
-            env::panic(b"Shoule be deposit mint price");
+            ContractError::InsufficientDeposit.panic();
         }
 
-        if self.minted_count >= self.total_count {
-            env::panic(b"All nfts are minted.");
+        let payer_id = env::predecessor_account_id();
+
+        if let Some(gate) = &self.mint_gate {
+            if let Some(error) = self.internal_raffle_mint_error(&payer_id) {
+                error.panic();
+            }
+            if self.minted_count >= self.total_count {
+                ContractError::SoldOut.panic();
+            }
+            return PromiseOrValue::Promise(self.internal_gated_mint(gate, receiver_id.into()));
         }
 
-        let mut token_id: u128 = 0;
-        
-        let mut rng: StdRng = SeedableRng::from_seed(env::random_seed().try_into().unwrap());
+        let kind = if self.internal_take_raffle_allowance(&payer_id) { MintKind::Raffle } else { MintKind::Public };
 
-        let remain_count: u128 = self.total_count - self.minted_count;
-        
-        let random_id: u128 = rng.gen_range(0, remain_count) + 1;
-        let mut passed_id: u128 = 0;
-
-        for idx in 0..self.total_count {
-            match self.is_minted_by_id.get(&idx) {
-                None => {
-                    passed_id += 1;
-
-                    if passed_id == random_id {
-                        token_id = idx;
-                        break;
-                    }
-                },
-                Some(_data) => {},
-            }
+        self.internal_record_mint(receiver_id.as_ref(), kind);
+        PromiseOrValue::Value(self.internal_mint(
+            receiver_id,
+            &payer_id,
+            env::attached_deposit(),
+            self.mint_price * MULTIPLYER,
+        ))
+    }
+
+    // Reserve mints skip the mint price, the caller only covers what the receiver's storage balance lacks
+    #[payable]
+    pub fn nft_mint_reserved(
+        &mut self,
+        receiver_id: ValidAccountId,
+    ) -> Token {
+        self.assert_role(Role::Minter);
+
+        self.internal_record_mint(receiver_id.as_ref(), MintKind::Reserved);
+        self.reserved_minted_count += 1;
+
+        self.internal_mint(receiver_id, &env::predecessor_account_id(), env::attached_deposit(), 0)
+    }
+
+    // Holders can burn their own tokens, which frees the storage they paid for
+    #[payable]
+    pub fn nft_burn(&mut self, token_id: TokenId) {
+        assert_one_yocto();
+        self.assert_transfers_not_paused();
+
+        self.internal_assert_token_owner(&token_id);
+        self.assert_not_escrowed(&token_id);
+        self.internal_burn(&token_id);
+    }
+
+    pub fn get_minted(&self) -> U128 {
+        U128(self.minted_count)
+    }
+
+    pub fn is_minted(&self, token_id: TokenId) -> bool {
+        match token_id.parse::<u128>() {
+            Ok(id) => id < self.total_count && self.internal_is_minted(id),
+            Err(_) => false,
         }
+    }
+}
 
-        self.is_minted_by_id.insert(&token_id, &true);
+impl Contract {
+    // `deposit` is what `payer_id` attached, whatever is left after the price and storage goes back to them
+    pub(crate) fn internal_mint(
+        &mut self,
+        receiver_id: ValidAccountId,
+        payer_id: &AccountId,
+        deposit: Balance,
+        price: Balance,
+    ) -> Token {
+        if self.minted_count >= self.total_count {
+            ContractError::SoldOut.panic();
+        }
+
+        self.assert_minted_ids_migrated();
+        self.assert_sale_not_ended();
+        self.assert_token_metadata_ready();
+
+        let token_id: u128 = selection::pick_unminted_id(
+            env::random_seed().try_into().unwrap(),
+            self.total_count,
+            self.minted_count,
+            |index| self.minted_ids.word(index),
+        );
+
+        self.minted_ids.insert(token_id);
         self.minted_count += 1;
 
-        let base_uri = match self.metadata.get().unwrap().base_uri {
-            None => {
-                "".to_string()
-            },
-            Some(data) => data,
-        };
+        let token_metadata = self.internal_token_metadata(&self.metadata.get().unwrap(), token_id);
+
+        self.internal_collect_proceeds(token_id, price);
+        self.internal_report_mint(price);
 
-        self.tokens.custom_mint(
+        // the holder pays for exactly what the token takes, metadata and this record included
+        let initial_storage_usage = env::storage_usage();
+        let token = self.tokens.custom_mint(
             token_id.to_string(), 
             // ValidAccountId::try_from(env::predecessor_account_id()).unwrap(), 
-            receiver_id,
-            Some(
-                TokenMetadata {
-                    title: Some(format!("{} #{}", self.metadata.get().unwrap().name, token_id.to_string())),
-                    description: Some(format!("{}, minted by Dao Nation", self.metadata.get().unwrap().name)),
-                    media: Some(format!("{}/{}.png", base_uri, token_id.to_string())), 
-                    media_hash: None, 
-                    copies: Some(1), 
-                    issued_at: None, 
-                    expires_at: None, 
-                    starts_at: None, 
-                    updated_at: None, 
-                    extra: None, 
-                    reference: Some(format!("{}/{}.json", base_uri, token_id.to_string())), 
-                    reference_hash: None
-                }
-            ),
-            self.mint_price * MULTIPLYER
+            receiver_id.clone(),
+            Some(token_metadata),
+        );
+        self.storage_bytes_by_token.insert(&token_id, &0);
+        let token_storage_bytes = env::storage_usage() - initial_storage_usage;
+        self.storage_bytes_by_token.insert(&token_id, &token_storage_bytes);
+
+        self.internal_fund_token_storage(receiver_id.as_ref(), payer_id, deposit, price, token_storage_bytes);
+
+        token
+    }
+
+    // Removes the token with its metadata and approvals, its id stays taken
+    pub(crate) fn internal_burn(&mut self, token_id: &TokenId) -> AccountId {
+        let owner_id = self
+            .tokens
+            .owner_by_id
+            .remove(token_id)
+            .unwrap_or_else(|| ContractError::TokenNotFound.panic_with(token_id));
+
+        if let Some(token_metadata_by_id) = &mut self.tokens.token_metadata_by_id {
+            token_metadata_by_id.remove(token_id);
+        }
+
+        if let Some(tokens_per_owner) = &mut self.tokens.tokens_per_owner {
+            let mut token_ids = tokens_per_owner.get(&owner_id).unwrap();
+            token_ids.remove(token_id);
+            if token_ids.is_empty() {
+                tokens_per_owner.remove(&owner_id);
+            } else {
+                tokens_per_owner.insert(&owner_id, &token_ids);
+            }
+        }
+
+        let approved_account_ids = self.tokens.approvals_by_id.as_mut().and_then(|by_id| by_id.remove(token_id));
+        if let Some(next_approval_id_by_id) = &mut self.tokens.next_approval_id_by_id {
+            next_approval_id_by_id.remove(token_id);
+        }
+
+        self.internal_release_storage(
+            &owner_id,
+            self.internal_token_storage_bytes(token_id) + storage::approvals_storage_bytes(approved_account_ids.as_ref()),
+        );
+        if let Ok(id) = token_id.parse::<u128>() {
+            self.storage_bytes_by_token.remove(&id);
+        }
+        self.burned_count += 1;
+
+        log_event("nft_burn", json!({ "owner_id": owner_id, "token_id": token_id }));
+
+        owner_id
+    }
+
+    pub(crate) fn internal_token_metadata(&self, contract_metadata: &NFTContractMetadata, token_id: u128) -> TokenMetadata {
+        let mut token_metadata = self.metadata_template.render(contract_metadata, token_id);
+        if let Some(upload) = self.metadata_uploads.get(&token_id) {
+            upload.apply(&mut token_metadata);
+        }
+
+        token_metadata
+    }
+
+    // Same as `nft_token`, without taking the contract by value
+    pub(crate) fn internal_token(&self, token_id: TokenId) -> Option<Token> {
+        let owner_id = self.tokens.owner_by_id.get(&token_id)?;
+        let metadata = self.tokens.token_metadata_by_id.as_ref().and_then(|by_id| by_id.get(&token_id));
+        let approved_account_ids = self
+            .tokens
+            .approvals_by_id
+            .as_ref()
+            .map(|by_id| by_id.get(&token_id).unwrap_or_default());
+
+        Some(Token { token_id, owner_id, metadata, approved_account_ids })
+    }
+}
+
+// Core methods are written out instead of using `impl_non_fungible_token_core!`
+// so that transfers can be halted while the contract is paused and storage follows the token.
+#[near_bindgen]
+impl NonFungibleTokenCore for Contract {
+    #[payable]
+    fn nft_transfer(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) {
+        assert_one_yocto();
+        self.assert_transfers_not_paused();
+
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) =
+            self.tokens.internal_transfer(&sender_id, receiver_id.as_ref(), &token_id, approval_id, memo);
+
+        self.internal_move_token_storage(&token_id, &previous_owner_id, receiver_id.as_ref(), approved_account_ids.as_ref(), false);
+    }
+
+    #[payable]
+    fn nft_transfer_call(
+        &mut self,
+        receiver_id: ValidAccountId,
+        token_id: TokenId,
+        approval_id: Option<u64>,
+        memo: Option<String>,
+        msg: String,
+    ) -> PromiseOrValue<bool> {
+        assert_one_yocto();
+        self.assert_transfers_not_paused();
+
+        let sender_id = env::predecessor_account_id();
+        let (previous_owner_id, approved_account_ids) =
+            self.tokens.internal_transfer(&sender_id, receiver_id.as_ref(), &token_id, approval_id, memo);
+
+        self.internal_move_token_storage(&token_id, &previous_owner_id, receiver_id.as_ref(), approved_account_ids.as_ref(), false);
+
+        ext_receiver::nft_on_transfer(
+            sender_id,
+            previous_owner_id.clone(),
+            token_id.clone(),
+            msg,
+            receiver_id.as_ref(),
+            NO_DEPOSIT,
+            env::prepaid_gas() - GAS_FOR_NFT_TRANSFER_CALL,
         )
+        .then(ext_self::nft_resolve_transfer(
+            previous_owner_id,
+            receiver_id.into(),
+            token_id,
+            approved_account_ids,
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_RESOLVE_TRANSFER,
+        ))
+        .into()
     }
 
-    pub fn get_minted(&self) -> u128 {
-        self.minted_count
+    fn nft_token(self, token_id: TokenId) -> Option<Token> {
+        self.tokens.nft_token(token_id)
+    }
+}
+
+#[near_bindgen]
+impl NonFungibleTokenResolver for Contract {
+    // Returns true if the token stayed with the receiver
+    #[private]
+    fn nft_resolve_transfer(
+        &mut self,
+        previous_owner_id: AccountId,
+        receiver_id: AccountId,
+        token_id: TokenId,
+        approved_account_ids: Option<HashMap<AccountId, u64>>,
+    ) -> bool {
+        let must_revert = match env::promise_result(0) {
+            PromiseResult::NotReady => env::abort(),
+            PromiseResult::Successful(value) => {
+                near_sdk::serde_json::from_slice::<bool>(&value).unwrap_or(true)
+            },
+            PromiseResult::Failed => true,
+        };
+
+        if !must_revert {
+            return true;
+        }
+
+        // the receiver may have passed the token on already
+        if self.tokens.owner_by_id.get(&token_id).as_ref() != Some(&receiver_id) {
+            return true;
+        }
+
+        self.tokens.internal_transfer_unguarded(&token_id, &receiver_id, &previous_owner_id);
+
+        // approvals the receiver added are dropped, the previous owner's come back
+        let approvals_by_id = self.tokens.approvals_by_id.as_mut().unwrap();
+        let receiver_approvals = approvals_by_id.remove(&token_id);
+        if let Some(previous_owner_approvals) = &approved_account_ids {
+            approvals_by_id.insert(&token_id, previous_owner_approvals);
+        }
+
+        self.internal_move_token_storage(&token_id, &receiver_id, &previous_owner_id, receiver_approvals.as_ref(), true);
+        self.internal_charge_storage(
+            &previous_owner_id,
+            storage::approvals_storage_bytes(approved_account_ids.as_ref()),
+            true,
+        );
+
+        false
     }
 }
 
-near_contract_standards::impl_non_fungible_token_core!(Contract, tokens);
-near_contract_standards::impl_non_fungible_token_approval!(Contract, tokens);
 near_contract_standards::impl_non_fungible_token_enumeration!(Contract, tokens);
 
 #[near_bindgen]
@@ -160,7 +543,6 @@ pub trait Custom_NonFungibleTokenCore {
         token_id: TokenId, 
         token_owner_id: ValidAccountId, 
         token_metadata: Option<TokenMetadata>,
-        price: Balance,
     ) -> Token;
 }
 
@@ -170,15 +552,12 @@ impl Custom_NonFungibleTokenCore for NonFungibleToken {
         token_id: TokenId,
         token_owner_id: ValidAccountId,
         token_metadata: Option<TokenMetadata>,
-        price: Balance,
     ) -> Token {
-        let initial_storage_usage = env::storage_usage();
-        
         if self.token_metadata_by_id.is_some() && token_metadata.is_none() {
-            env::panic(b"Must provide metadata");
+            ContractError::MissingTokenMetadata.panic();
         }
         if self.owner_by_id.get(&token_id).is_some() {
-            env::panic(b"token_id must be unique");
+            ContractError::DuplicateTokenId.panic();
         }
 
         let owner_id: AccountId = token_owner_id.into();
@@ -208,25 +587,20 @@ impl Custom_NonFungibleTokenCore for NonFungibleToken {
         let approved_account_ids =
             if self.approvals_by_id.is_some() { Some(HashMap::new()) } else { None };
 
-        // Return any extra attached deposit not used for storage
-        refund_deposit(env::storage_usage() - initial_storage_usage, price);
-
         Token { token_id, owner_id, metadata: token_metadata, approved_account_ids }
     }
 }
 
-pub(crate) fn refund_deposit(storage_used: u64, price: Balance) {
+pub(crate) fn refund_deposit(storage_used: u64) {
     //get how much it would cost to store the information
-    let required_cost = env::storage_byte_cost() * Balance::from(storage_used) + price;
+    let required_cost = env::storage_byte_cost() * Balance::from(storage_used);
     //get the attached deposit
     let attached_deposit = env::attached_deposit();
 
     //make sure that the attached deposit is greater than or equal to the required cost
-    assert!(
-        required_cost <= attached_deposit,
-        "Must attach {} yoctoNEAR to cover storage",
-        required_cost,
-    );
+    if required_cost > attached_deposit {
+        ContractError::InsufficientStorageDeposit.panic_with(format!("{} yoctoNEAR required", required_cost));
+    }
 
     //get the refund amount from the attached deposit - required cost
     let refund = attached_deposit - required_cost;
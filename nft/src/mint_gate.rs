@@ -0,0 +1,191 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json};
+
+const GAS_FOR_GATE_BALANCE: Gas = 5_000_000_000_000;
+// Covers the mint itself and the report it sends to the launchpad
+const GAS_FOR_ON_MINT_GATE: Gas = 30_000_000_000_000;
+
+// Token a receiver has to hold before `nft_mint` goes through
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MintGate {
+    // At least `min_balance` of the FT by `ft_balance_of`
+    FungibleToken { contract_id: AccountId, min_balance: U128 },
+    // At least `min_tokens` tokens of the NFT by `nft_supply_for_owner`
+    NonFungibleToken { contract_id: AccountId, min_tokens: U128 },
+}
+
+// How `MintGate` is kept in state
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) enum StoredMintGate {
+    FungibleToken { contract_id: AccountId, min_balance: Balance },
+    NonFungibleToken { contract_id: AccountId, min_tokens: u128 },
+}
+
+impl From<MintGate> for StoredMintGate {
+    fn from(gate: MintGate) -> Self {
+        match gate {
+            MintGate::FungibleToken { contract_id, min_balance } => {
+                StoredMintGate::FungibleToken { contract_id, min_balance: min_balance.0 }
+            },
+            MintGate::NonFungibleToken { contract_id, min_tokens } => {
+                StoredMintGate::NonFungibleToken { contract_id, min_tokens: min_tokens.0 }
+            },
+        }
+    }
+}
+
+impl From<&StoredMintGate> for MintGate {
+    fn from(gate: &StoredMintGate) -> Self {
+        match gate {
+            StoredMintGate::FungibleToken { contract_id, min_balance } => {
+                MintGate::FungibleToken { contract_id: contract_id.clone(), min_balance: U128(*min_balance) }
+            },
+            StoredMintGate::NonFungibleToken { contract_id, min_tokens } => {
+                MintGate::NonFungibleToken { contract_id: contract_id.clone(), min_tokens: U128(*min_tokens) }
+            },
+        }
+    }
+}
+
+impl StoredMintGate {
+    fn contract_id(&self) -> &AccountId {
+        match self {
+            StoredMintGate::FungibleToken { contract_id, .. } | StoredMintGate::NonFungibleToken { contract_id, .. } => {
+                contract_id
+            },
+        }
+    }
+
+    fn min_held(&self) -> u128 {
+        match self {
+            StoredMintGate::FungibleToken { min_balance, .. } => *min_balance,
+            StoredMintGate::NonFungibleToken { min_tokens, .. } => *min_tokens,
+        }
+    }
+}
+
+#[ext_contract(ext_gate)]
+pub trait GateToken {
+    fn ft_balance_of(&self, account_id: AccountId) -> U128;
+    fn nft_supply_for_owner(&self, account_id: AccountId) -> U128;
+}
+
+#[ext_contract(ext_mint_gate)]
+pub trait MintGateCallbacks {
+    fn on_mint_gate(&mut self, receiver_id: AccountId, payer_id: AccountId, deposit: U128, min_held: U128) -> Option<Token>;
+}
+
+#[near_bindgen]
+impl Contract {
+    // Restricts `nft_mint` to receivers holding the gate token, `None` opens minting again.
+    // Reserve mints are not gated.
+    pub fn set_mint_gate(&mut self, gate: Option<MintGate>) {
+        self.assert_owner();
+
+        let gate = gate.map(StoredMintGate::from);
+        if let Some(gate) = &gate {
+            if ValidAccountId::try_from(gate.contract_id().clone()).is_err() {
+                ContractError::InvalidMintGate.panic_with("invalid contract_id");
+            }
+            if gate.min_held() == 0 {
+                ContractError::InvalidMintGate.panic_with("minimum must be above 0");
+            }
+        }
+
+        self.mint_gate = gate;
+
+        log_event("mint_gate_updated", json!({ "gate": self.get_mint_gate() }));
+    }
+
+    pub fn get_mint_gate(&self) -> Option<MintGate> {
+        self.mint_gate.as_ref().map(MintGate::from)
+    }
+
+    // Finishes a gated mint once the gate contract answered. The deposit is refunded in full to the
+    // payer when the receiver holds too little or the mint can't go through anymore, and `null` tells
+    // a calling contract such as the INO registry that nothing was minted.
+    #[private]
+    pub fn on_mint_gate(&mut self, receiver_id: AccountId, payer_id: AccountId, deposit: U128, min_held: U128) -> Option<Token> {
+        let held = match env::promise_result(0) {
+            PromiseResult::Successful(value) => serde_json::from_slice::<U128>(&value).ok(),
+            _ => None,
+        };
+
+        let error = match held {
+            Some(held) if held.0 >= min_held.0 => self.internal_mint_error(&receiver_id, &payer_id, deposit.0),
+            _ => Some(ContractError::MintGateNotMet),
+        };
+        if let Some(error) = error {
+            Promise::new(payer_id.clone()).transfer(deposit.0);
+
+            log_event("mint_refunded", json!({
+                "receiver_id": receiver_id,
+                "payer_id": payer_id,
+                "amount": deposit,
+                "error": error.code(),
+            }));
+
+            return None;
+        }
+
+        let kind = if self.internal_take_raffle_allowance(&payer_id) { MintKind::Raffle } else { MintKind::Public };
+
+        self.internal_record_mint(&receiver_id, kind);
+        Some(self.internal_mint(
+            ValidAccountId::try_from(receiver_id).unwrap(),
+            &payer_id,
+            deposit.0,
+            self.mint_price * MULTIPLYER,
+        ))
+    }
+}
+
+impl Contract {
+    // Asks the gate contract what `receiver_id` holds and mints in `on_mint_gate`
+    pub(crate) fn internal_gated_mint(&self, gate: &StoredMintGate, receiver_id: AccountId) -> Promise {
+        let check = match gate {
+            StoredMintGate::FungibleToken { contract_id, .. } => {
+                ext_gate::ft_balance_of(receiver_id.clone(), contract_id, NO_DEPOSIT, GAS_FOR_GATE_BALANCE)
+            },
+            StoredMintGate::NonFungibleToken { contract_id, .. } => {
+                ext_gate::nft_supply_for_owner(receiver_id.clone(), contract_id, NO_DEPOSIT, GAS_FOR_GATE_BALANCE)
+            },
+        };
+
+        check.then(ext_mint_gate::on_mint_gate(
+            receiver_id,
+            env::predecessor_account_id(),
+            U128(env::attached_deposit()),
+            U128(gate.min_held()),
+            &env::current_account_id(),
+            NO_DEPOSIT,
+            GAS_FOR_ON_MINT_GATE,
+        ))
+    }
+
+    // The checks `nft_mint` panics on, for the callback which has to refund instead
+    fn internal_mint_error(&self, receiver_id: &AccountId, payer_id: &AccountId, deposit: Balance) -> Option<ContractError> {
+        let price = self.mint_price * MULTIPLYER;
+
+        if self.mint_paused {
+            Some(ContractError::MintPaused)
+        } else if let Some(error) = self.internal_raffle_mint_error(payer_id) {
+            Some(error)
+        } else if self.minted_count >= self.total_count {
+            Some(ContractError::SoldOut)
+        } else if self.legacy_minted_ids.is_some() {
+            Some(ContractError::MigrationPending)
+        } else if self.internal_raise_failed() {
+            Some(ContractError::SaleEnded)
+        } else if self.loaded_metadata_count > 0 && self.loaded_metadata_count < self.total_count {
+            Some(ContractError::MetadataIncomplete)
+        } else if deposit < price + self.internal_storage_shortfall(receiver_id, self.internal_token_storage_estimate()) {
+            Some(ContractError::InsufficientStorageDeposit)
+        } else {
+            None
+        }
+    }
+}
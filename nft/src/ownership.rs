@@ -0,0 +1,26 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[near_bindgen]
+impl Contract {
+    pub fn propose_owner(&mut self, new_owner_id: ValidAccountId) {
+        self.internal_propose_owner(new_owner_id.clone().into());
+
+        log_event("owner_proposed", json!({ "owner_id": self.tokens.owner_id, "pending_owner_id": new_owner_id }));
+    }
+
+    pub fn accept_ownership(&mut self) {
+        let old_owner_id = self.internal_accept_ownership();
+
+        log_event("owner_changed", json!({ "old_owner_id": old_owner_id, "new_owner_id": self.tokens.owner_id }));
+    }
+
+    pub fn get_owner(&self) -> AccountId {
+        self.tokens.owner_id.clone()
+    }
+
+    pub fn get_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner.clone()
+    }
+}
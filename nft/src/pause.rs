@@ -0,0 +1,50 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseStatus {
+    pub mint_paused: bool,
+    pub transfers_paused: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn set_mint_paused(&mut self, paused: bool) {
+        self.assert_role(Role::Pauser);
+
+        self.mint_paused = paused;
+
+        log_event("mint_paused", json!({ "paused": paused, "by": env::predecessor_account_id() }));
+    }
+
+    pub fn set_transfers_paused(&mut self, paused: bool) {
+        self.assert_role(Role::Pauser);
+
+        self.transfers_paused = paused;
+
+        log_event("transfers_paused", json!({ "paused": paused, "by": env::predecessor_account_id() }));
+    }
+
+    pub fn get_pause_status(&self) -> PauseStatus {
+        PauseStatus {
+            mint_paused: self.mint_paused,
+            transfers_paused: self.transfers_paused,
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn assert_mint_not_paused(&self) {
+        if self.mint_paused {
+            ContractError::MintPaused.panic();
+        }
+    }
+
+    pub(crate) fn assert_transfers_not_paused(&self) {
+        if self.transfers_paused {
+            ContractError::TransfersPaused.panic();
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+// Keeps `draw_raffle` within a single call's gas
+const MAX_RAFFLE_WINNERS: u64 = 500;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Raffle {
+    // Block timestamps in nanoseconds. Entries are taken in [entry_start, entry_end), winners mint
+    // until claim_end and public minting opens after it.
+    pub entry_start: u64,
+    pub entry_end: u64,
+    pub claim_end: u64,
+    pub winners: u64,
+    // Mints each winner may make during the claim window
+    pub allowance: u32,
+    pub entry_deposit: Balance,
+    // Entry deposits not reclaimed yet
+    pub deposits: Balance,
+    pub drawn: bool,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RaffleEntry {
+    pub deposit: Balance,
+    pub won: bool,
+    pub allowance_left: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleView {
+    pub entry_start: U64,
+    pub entry_end: U64,
+    pub claim_end: U64,
+    pub winners: U64,
+    pub allowance: u32,
+    pub entry_deposit: U128,
+    pub entrants: U64,
+    pub drawn: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RaffleEntryView {
+    pub deposit: U128,
+    pub won: bool,
+    pub allowance_left: u32,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Puts a raffle in front of the public sale: until `claim_end` only drawn winners can mint,
+    // each up to `allowance` tokens
+    pub fn set_raffle(
+        &mut self,
+        entry_start: U64,
+        entry_end: U64,
+        claim_end: U64,
+        winners: U64,
+        allowance: u32,
+        entry_deposit: U128,
+    ) {
+        self.assert_owner();
+
+        if self.minted_count > 0 || !self.raffle_entries.is_empty() {
+            ContractError::SaleLocked.panic();
+        }
+        if entry_start.0 >= entry_end.0 || entry_end.0 > claim_end.0 || entry_end.0 <= env::block_timestamp() {
+            ContractError::InvalidRaffle.panic_with("entry_start < entry_end <= claim_end, entry_end in the future");
+        }
+        if winners.0 == 0 || winners.0 > MAX_RAFFLE_WINNERS || allowance == 0 {
+            ContractError::InvalidRaffle.panic_with(format!("between 1 and {} winners, allowance above 0", MAX_RAFFLE_WINNERS));
+        }
+        if u128::from(winners.0) * u128::from(allowance) > self.total_count {
+            ContractError::InvalidRaffle.panic_with("winner allowances exceed the supply");
+        }
+
+        self.raffle = Some(Raffle {
+            entry_start: entry_start.0,
+            entry_end: entry_end.0,
+            claim_end: claim_end.0,
+            winners: winners.0,
+            allowance,
+            entry_deposit: entry_deposit.0,
+            deposits: 0,
+            drawn: false,
+        });
+
+        log_event("raffle_set", json!({
+            "entry_start": entry_start,
+            "entry_end": entry_end,
+            "claim_end": claim_end,
+            "winners": winners,
+            "allowance": allowance,
+            "entry_deposit": entry_deposit,
+        }));
+    }
+
+    // Enters the caller. The entry deposit is held until the draw, anything attached beyond it goes
+    // to the caller's storage balance, which pays for the entry.
+    #[payable]
+    pub fn enter_raffle(&mut self) {
+        let raffle = self.raffle.as_ref().unwrap_or_else(|| ContractError::NoRaffle.panic());
+        let now = env::block_timestamp();
+        if now < raffle.entry_start || now >= raffle.entry_end {
+            ContractError::RaffleClosed.panic();
+        }
+
+        let account_id = env::predecessor_account_id();
+        if self.raffle_entries.get(&account_id).is_some() {
+            ContractError::AlreadyEntered.panic();
+        }
+
+        let entry_deposit = raffle.entry_deposit;
+        let attached = env::attached_deposit();
+        if attached < entry_deposit {
+            ContractError::InsufficientDeposit.panic_with(format!("{} yoctoNEAR required", entry_deposit));
+        }
+        if attached > entry_deposit || self.storage_accounts.get(&account_id).is_none() {
+            self.internal_deposit_for_storage(&account_id, attached - entry_deposit);
+        }
+
+        let initial_storage_usage = env::storage_usage();
+        self.raffle_entries.insert(&account_id, &RaffleEntry {
+            deposit: entry_deposit,
+            won: false,
+            allowance_left: 0,
+        });
+        self.internal_charge_storage(&account_id, env::storage_usage() - initial_storage_usage, false);
+
+        self.raffle.as_mut().unwrap().deposits += entry_deposit;
+
+        log_event("raffle_entered", json!({ "account_id": account_id, "deposit": U128(entry_deposit) }));
+    }
+
+    // Anyone can draw once entries closed. Every entrant wins when there are fewer than `winners`.
+    pub fn draw_raffle(&mut self) {
+        let raffle = self.raffle.as_ref().unwrap_or_else(|| ContractError::NoRaffle.panic());
+        if raffle.drawn {
+            ContractError::RaffleAlreadyDrawn.panic();
+        }
+        if env::block_timestamp() < raffle.entry_end {
+            ContractError::RaffleOpen.panic();
+        }
+
+        let entrants = self.raffle_entries.len();
+        let winners = raffle.winners.min(entrants);
+        let allowance = raffle.allowance;
+        let accounts = self.raffle_entries.keys_as_vector();
+        let seed = env::random_seed();
+
+        let mut won = vec![false; entrants as usize];
+        let mut nonce = 0;
+        for _ in 0..winners {
+            // Redraw when an account already won, so every account left stays equally likely
+            let index = loop {
+                let index = random_index(&seed, nonce, entrants);
+                nonce += 1;
+                if !won[index as usize] {
+                    break index;
+                }
+            };
+            won[index as usize] = true;
+
+            let account_id = accounts.get(index).unwrap();
+            let mut entry = self.raffle_entries.get(&account_id).unwrap();
+            entry.won = true;
+            entry.allowance_left = allowance;
+            self.raffle_entries.insert(&account_id, &entry);
+
+            log_event("raffle_won", json!({ "account_id": account_id, "allowance": allowance }));
+        }
+
+        self.raffle.as_mut().unwrap().drawn = true;
+
+        log_event("raffle_drawn", json!({ "entrants": U64(entrants), "winners": U64(winners) }));
+    }
+
+    // Returns the caller's entry deposit once the raffle is drawn. Losers leave the raffle, winners
+    // keep their entry until the allowance is spent or the claim window ends.
+    pub fn reclaim_raffle_deposit(&mut self) -> U128 {
+        let raffle = self.raffle.as_ref().unwrap_or_else(|| ContractError::NoRaffle.panic());
+        if !raffle.drawn {
+            ContractError::RaffleNotDrawn.panic();
+        }
+        let claim_open = env::block_timestamp() < raffle.claim_end;
+
+        let account_id = env::predecessor_account_id();
+        let mut entry = self
+            .raffle_entries
+            .get(&account_id)
+            .unwrap_or_else(|| ContractError::NothingToRefund.panic());
+        let deposit = entry.deposit;
+
+        if entry.won && entry.allowance_left > 0 && claim_open {
+            if deposit == 0 {
+                ContractError::NothingToRefund.panic();
+            }
+            entry.deposit = 0;
+            self.raffle_entries.insert(&account_id, &entry);
+        } else {
+            self.internal_remove_raffle_entry(&account_id);
+        }
+
+        self.raffle.as_mut().unwrap().deposits -= deposit;
+        if deposit > 0 {
+            Promise::new(account_id.clone()).transfer(deposit);
+        }
+
+        log_event("raffle_deposit_reclaimed", json!({ "account_id": account_id, "amount": U128(deposit) }));
+
+        U128(deposit)
+    }
+
+    pub fn get_raffle(&self) -> Option<RaffleView> {
+        self.raffle.as_ref().map(|raffle| RaffleView {
+            entry_start: U64(raffle.entry_start),
+            entry_end: U64(raffle.entry_end),
+            claim_end: U64(raffle.claim_end),
+            winners: U64(raffle.winners),
+            allowance: raffle.allowance,
+            entry_deposit: U128(raffle.entry_deposit),
+            entrants: U64(self.raffle_entries.len()),
+            drawn: raffle.drawn,
+        })
+    }
+
+    pub fn get_raffle_entry(&self, account_id: ValidAccountId) -> Option<RaffleEntryView> {
+        self.raffle_entries.get(account_id.as_ref()).map(|entry| RaffleEntryView {
+            deposit: U128(entry.deposit),
+            won: entry.won,
+            allowance_left: entry.allowance_left,
+        })
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_raffle_gates_mint(&self) -> bool {
+        match &self.raffle {
+            Some(raffle) => env::block_timestamp() < raffle.claim_end,
+            None => false,
+        }
+    }
+
+    // Why `account_id` can't mint right now because of the raffle, if it can't
+    pub(crate) fn internal_raffle_mint_error(&self, account_id: &AccountId) -> Option<ContractError> {
+        if !self.internal_raffle_gates_mint() {
+            return None;
+        }
+        if !self.raffle.as_ref().unwrap().drawn {
+            return Some(ContractError::RaffleNotDrawn);
+        }
+
+        match self.raffle_entries.get(account_id) {
+            Some(entry) if entry.allowance_left > 0 => None,
+            _ => Some(ContractError::NoMintAllowance),
+        }
+    }
+
+    // Spends one mint of the payer's allowance while the raffle gates minting. Returns whether it did.
+    pub(crate) fn internal_take_raffle_allowance(&mut self, account_id: &AccountId) -> bool {
+        if let Some(error) = self.internal_raffle_mint_error(account_id) {
+            error.panic();
+        }
+        if !self.internal_raffle_gates_mint() {
+            return false;
+        }
+
+        let mut entry = self.raffle_entries.get(account_id).unwrap();
+        entry.allowance_left -= 1;
+
+        if entry.allowance_left == 0 && entry.deposit == 0 {
+            self.internal_remove_raffle_entry(account_id);
+        } else {
+            self.raffle_entries.insert(account_id, &entry);
+        }
+
+        true
+    }
+
+    pub(crate) fn internal_raffle_deposits(&self) -> Balance {
+        self.raffle.as_ref().map_or(0, |raffle| raffle.deposits)
+    }
+
+    fn internal_remove_raffle_entry(&mut self, account_id: &AccountId) {
+        let initial_storage_usage = env::storage_usage();
+        self.raffle_entries.remove(account_id);
+        self.internal_release_storage(account_id, initial_storage_usage - env::storage_usage());
+    }
+}
+
+fn random_index(seed: &[u8], nonce: u64, entrants: u64) -> u64 {
+    let mut input = seed.to_vec();
+    input.extend_from_slice(&nonce.to_le_bytes());
+    let hash = env::sha256(&input);
+
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(bytes) % entrants
+}
@@ -0,0 +1,69 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum Role {
+    Admin,
+    Minter,
+    Pauser,
+    Treasurer,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn grant_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role(Role::Admin);
+
+        if self.internal_grant_role(account_id.as_ref(), role) {
+            log_event("role_granted", json!({ "account_id": account_id, "role": role, "by": env::predecessor_account_id() }));
+        }
+    }
+
+    pub fn revoke_role(&mut self, account_id: ValidAccountId, role: Role) {
+        self.assert_role(Role::Admin);
+
+        if self.internal_revoke_role(account_id.as_ref(), role) {
+            log_event("role_revoked", json!({ "account_id": account_id, "role": role, "by": env::predecessor_account_id() }));
+        }
+    }
+
+    pub fn has_role(&self, account_id: ValidAccountId, role: Role) -> bool {
+        self.internal_has_role(account_id.as_ref(), role)
+    }
+
+    pub fn get_roles(&self, account_id: ValidAccountId) -> Vec<Role> {
+        self.roles_by_account.get(account_id.as_ref()).unwrap_or_default()
+    }
+
+    pub fn get_role_holders(&self) -> Vec<(AccountId, Vec<Role>)> {
+        self.roles_by_account.to_vec()
+    }
+}
+
+impl AccessControl for Contract {
+    type Role = Role;
+
+    const ADMIN: Role = Role::Admin;
+
+    fn owner_id(&self) -> &AccountId {
+        &self.tokens.owner_id
+    }
+
+    fn owner_id_mut(&mut self) -> &mut AccountId {
+        &mut self.tokens.owner_id
+    }
+
+    fn pending_owner_mut(&mut self) -> &mut Option<AccountId> {
+        &mut self.pending_owner
+    }
+
+    fn roles_by_account(&self) -> &UnorderedMap<AccountId, Vec<Role>> {
+        &self.roles_by_account
+    }
+
+    fn roles_by_account_mut(&mut self) -> &mut UnorderedMap<AccountId, Vec<Role>> {
+        &mut self.roles_by_account
+    }
+}
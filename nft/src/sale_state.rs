@@ -0,0 +1,110 @@
+use crate::*;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum SalePhase {
+    // Per-token metadata upload has started but not every id is loaded yet
+    Preparing,
+    Open,
+    // Only raffle winners can mint, up to their allowance
+    Raffle,
+    Paused,
+    SoldOut,
+    // The soft cap deadline passed unmet, holders can claim refunds
+    Failed,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct MintCounts {
+    pub reserved: u128,
+    pub public: u128,
+    pub raffle: u128,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum MintKind {
+    Public,
+    Reserved,
+    // Paid like a public mint, out of an allowance won in the raffle
+    Raffle,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintCountsView {
+    pub reserved: U128,
+    pub public: U128,
+    pub raffle: U128,
+    pub total: U128,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleState {
+    pub total: U128,
+    pub minted: U128,
+    pub remaining: U128,
+    pub burned: U128,
+    pub reserved: U128,
+    pub price: U128,
+    pub phase: SalePhase,
+    pub mint_paused: bool,
+    pub transfers_paused: bool,
+}
+
+#[near_bindgen]
+impl Contract {
+    pub fn get_sale_state(&self) -> SaleState {
+        SaleState {
+            total: U128(self.total_count),
+            minted: U128(self.minted_count),
+            remaining: U128(self.total_count - self.minted_count),
+            burned: U128(self.burned_count),
+            reserved: U128(self.reserved_minted_count),
+            price: U128(self.mint_price * MULTIPLYER),
+            phase: self.internal_sale_phase(),
+            mint_paused: self.mint_paused,
+            transfers_paused: self.transfers_paused,
+        }
+    }
+
+    // Tokens minted to `account_id` in each phase, regardless of later transfers
+    pub fn get_mint_counts_for_owner(&self, account_id: ValidAccountId) -> MintCountsView {
+        let counts = self.mint_counts_by_account.get(account_id.as_ref()).unwrap_or_default();
+
+        MintCountsView {
+            reserved: U128(counts.reserved),
+            public: U128(counts.public),
+            raffle: U128(counts.raffle),
+            total: U128(counts.reserved + counts.public + counts.raffle),
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_sale_phase(&self) -> SalePhase {
+        if self.minted_count >= self.total_count {
+            SalePhase::SoldOut
+        } else if self.internal_raise_failed() {
+            SalePhase::Failed
+        } else if self.mint_paused {
+            SalePhase::Paused
+        } else if self.loaded_metadata_count > 0 && self.loaded_metadata_count < self.total_count {
+            SalePhase::Preparing
+        } else if self.internal_raffle_gates_mint() {
+            SalePhase::Raffle
+        } else {
+            SalePhase::Open
+        }
+    }
+
+    pub(crate) fn internal_record_mint(&mut self, receiver_id: &AccountId, kind: MintKind) {
+        let mut counts = self.mint_counts_by_account.get(receiver_id).unwrap_or_default();
+        match kind {
+            MintKind::Public => counts.public += 1,
+            MintKind::Reserved => counts.reserved += 1,
+            MintKind::Raffle => counts.raffle += 1,
+        }
+        self.mint_counts_by_account.insert(receiver_id, &counts);
+    }
+}
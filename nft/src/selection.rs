@@ -0,0 +1,171 @@
+use ino_common::ContractError;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::bitmap::BITS_PER_WORD;
+
+// Picks one of the ids in `0..total_count` that is not minted yet, each free id being equally likely.
+// `word_at(n)` returns the minted bitmap word covering ids `n * 128..(n + 1) * 128`, so full words are
+// skipped with a single read. Kept free of contract state so the choice is reproducible from the seed alone.
+pub fn pick_unminted_id<F>(seed: [u8; 32], total_count: u128, minted_count: u128, word_at: F) -> u128
+where
+    F: Fn(u64) -> u128,
+{
+    if minted_count >= total_count {
+        ContractError::SoldOut.panic();
+    }
+
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+    let remain_count: u128 = total_count - minted_count;
+
+    let random_id: u128 = rng.gen_range(0, remain_count) + 1;
+    let mut passed_id: u128 = 0;
+
+    let word_count = (total_count + BITS_PER_WORD - 1) / BITS_PER_WORD;
+
+    for word_index in 0..word_count {
+        let first_id = word_index * BITS_PER_WORD;
+        let ids_in_word = std::cmp::min(BITS_PER_WORD, total_count - first_id);
+        let valid_bits = if ids_in_word == BITS_PER_WORD { u128::MAX } else { (1u128 << ids_in_word) - 1 };
+
+        let free_bits = !word_at(word_index as u64) & valid_bits;
+        let free_count = free_bits.count_ones() as u128;
+
+        if passed_id + free_count < random_id {
+            passed_id += free_count;
+            continue;
+        }
+
+        for bit in 0..ids_in_word {
+            if free_bits & (1u128 << bit) != 0 {
+                passed_id += 1;
+
+                if passed_id == random_id {
+                    return first_id + bit;
+                }
+            }
+        }
+    }
+
+    ContractError::MintedCountOutOfSync.panic()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::VMContextBuilder;
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    // Packs a list of minted flags into bitmap words the way `MintedBitmap` stores them
+    fn words(minted: &[bool]) -> Vec<u128> {
+        let mut words = vec![0u128; (minted.len() + 127) / 128];
+
+        for (id, is_minted) in minted.iter().enumerate() {
+            if *is_minted {
+                words[id / 128] |= 1u128 << (id % 128);
+            }
+        }
+
+        words
+    }
+
+    fn seeds(count: usize, from: u64) -> Vec<[u8; 32]> {
+        let mut rng: StdRng = SeedableRng::seed_from_u64(from);
+
+        (0..count).map(|_| rng.gen()).collect()
+    }
+
+    fn sell_out(total_count: u128, seeds: &[[u8; 32]]) -> Vec<u128> {
+        let mut minted = vec![false; total_count as usize];
+        let mut picked = Vec::new();
+
+        for (minted_count, seed) in seeds.iter().take(total_count as usize).enumerate() {
+            let bitmap = words(&minted);
+            let id = pick_unminted_id(*seed, total_count, minted_count as u128, |index| bitmap[index as usize]);
+
+            assert!(!minted[id as usize], "id {} was picked twice", id);
+            minted[id as usize] = true;
+            picked.push(id);
+        }
+
+        picked
+    }
+
+    #[test]
+    fn same_seed_picks_same_id() {
+        let seed = [7u8; 32];
+        let minted = vec![false, true, false, false, true];
+        let bitmap = words(&minted);
+
+        let first = pick_unminted_id(seed, 5, 2, |index| bitmap[index as usize]);
+        let second = pick_unminted_id(seed, 5, 2, |index| bitmap[index as usize]);
+
+        assert_eq!(first, second);
+        assert!(!minted[first as usize]);
+    }
+
+    #[test]
+    fn last_free_id_is_always_picked() {
+        for seed in seeds(50, 1) {
+            let id = pick_unminted_id(seed, 4, 3, |_| 0b1011);
+            assert_eq!(id, 2);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "E300: All nfts are minted.")]
+    fn sold_out_collection_panics() {
+        testing_env!(VMContextBuilder::new().build());
+        pick_unminted_id([0u8; 32], 3, 3, |_| 0b111);
+    }
+
+    #[test]
+    fn full_sellout_mints_every_id_exactly_once() {
+        // sizes around word boundaries exercise the partially filled last word
+        for (round, total_count) in [1u128, 2, 3, 10, 57, 127, 128, 129, 300].iter().enumerate() {
+            let picked = sell_out(*total_count, &seeds(*total_count as usize, round as u64));
+
+            let mut sorted = picked.clone();
+            sorted.sort_unstable();
+            assert_eq!(sorted, (0..*total_count).collect::<Vec<u128>>());
+        }
+    }
+
+    #[test]
+    fn full_sellout_holds_for_many_seeds() {
+        for round in 0..200u64 {
+            let picked = sell_out(20, &seeds(20, 1_000 + round));
+            assert_eq!(picked.len(), 20);
+        }
+    }
+
+    #[test]
+    fn selection_is_uniform_over_free_ids() {
+        const TOTAL_COUNT: u128 = 10;
+        const SAMPLES: usize = 20_000;
+
+        // ids 3 and 7 are taken, the remaining eight should share the picks evenly
+        let minted = |id: u128| id == 3 || id == 7;
+        let bitmap = (1u128 << 3) | (1u128 << 7);
+        let mut hits = vec![0usize; TOTAL_COUNT as usize];
+
+        for seed in seeds(SAMPLES, 42) {
+            hits[pick_unminted_id(seed, TOTAL_COUNT, 2, |_| bitmap) as usize] += 1;
+        }
+
+        assert_eq!(hits[3], 0);
+        assert_eq!(hits[7], 0);
+
+        let expected = SAMPLES as f64 / 8.0;
+        let chi_square: f64 = hits
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| !minted(*id as u128))
+            .map(|(_, count)| (*count as f64 - expected).powi(2) / expected)
+            .sum();
+
+        // 7 degrees of freedom, p = 0.001
+        assert!(chi_square < 24.32, "chi-square {} suggests a biased selection: {:?}", chi_square, hits);
+    }
+}
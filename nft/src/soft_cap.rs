@@ -0,0 +1,132 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct SoftCap {
+    // Public mints needed for the raise to succeed
+    pub min_sold: u128,
+    // Block timestamp in nanoseconds
+    pub deadline: u64,
+    pub met: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SoftCapView {
+    pub min_sold: U128,
+    pub sold: U128,
+    pub deadline: U64,
+    pub met: bool,
+    pub failed: bool,
+    pub escrowed: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Holds public mint proceeds in escrow until `min_sold` tokens sold. If `deadline` passes
+    // first, minting stops and holders can get their price back with `claim_refund`.
+    pub fn set_soft_cap(&mut self, min_sold: U128, deadline: U64) {
+        self.assert_owner();
+
+        if self.minted_count > 0 {
+            ContractError::SaleLocked.panic();
+        }
+        if min_sold.0 == 0 || min_sold.0 > self.total_count || deadline.0 <= env::block_timestamp() {
+            ContractError::InvalidSoftCap.panic();
+        }
+
+        self.soft_cap = Some(SoftCap { min_sold: min_sold.0, deadline: deadline.0, met: false });
+
+        log_event("soft_cap_set", json!({ "min_sold": min_sold, "deadline": deadline }));
+    }
+
+    pub fn get_soft_cap(&self) -> Option<SoftCapView> {
+        self.soft_cap.as_ref().map(|soft_cap| SoftCapView {
+            min_sold: U128(soft_cap.min_sold),
+            sold: U128(self.internal_public_sold()),
+            deadline: U64(soft_cap.deadline),
+            met: soft_cap.met,
+            failed: self.internal_raise_failed(),
+            escrowed: U128(self.escrowed_proceeds),
+        })
+    }
+
+    // Burns a token of a failed raise and refunds what was paid to mint it to its holder
+    #[payable]
+    pub fn claim_refund(&mut self, token_id: TokenId) -> Promise {
+        assert_one_yocto();
+
+        if !self.internal_raise_failed() {
+            ContractError::RefundUnavailable.panic();
+        }
+
+        let holder_id = self.internal_assert_token_owner(&token_id);
+        let id: u128 = token_id.parse().unwrap();
+        let paid = self
+            .paid_by_token
+            .remove(&id)
+            .unwrap_or_else(|| ContractError::NothingToRefund.panic());
+
+        self.internal_burn(&token_id);
+        self.escrowed_proceeds -= paid;
+
+        log_event("refund_claimed", json!({ "holder_id": holder_id, "token_id": token_id, "amount": U128(paid) }));
+
+        Promise::new(holder_id).transfer(paid)
+    }
+}
+
+impl Contract {
+    fn internal_public_sold(&self) -> u128 {
+        self.minted_count - self.reserved_minted_count
+    }
+
+    pub(crate) fn internal_raise_failed(&self) -> bool {
+        match &self.soft_cap {
+            Some(soft_cap) => !soft_cap.met && env::block_timestamp() > soft_cap.deadline,
+            None => false,
+        }
+    }
+
+    // Until the cap is met a burned token would strand its escrowed price, which only
+    // `claim_refund` gives back
+    pub(crate) fn assert_not_escrowed(&self, token_id: &TokenId) {
+        let escrow_open = self.soft_cap.as_ref().map_or(false, |soft_cap| !soft_cap.met);
+        let paid = token_id.parse::<u128>().ok().and_then(|id| self.paid_by_token.get(&id)).is_some();
+        if escrow_open && paid {
+            ContractError::ProceedsEscrowed.panic();
+        }
+    }
+
+    pub(crate) fn assert_sale_not_ended(&self) {
+        if self.internal_raise_failed() {
+            ContractError::SaleEnded.panic();
+        }
+    }
+
+    // Escrows what a mint paid while the cap is not met, and releases the escrow once it is
+    pub(crate) fn internal_collect_proceeds(&mut self, token_id: u128, price: Balance) {
+        let min_sold = match &self.soft_cap {
+            Some(soft_cap) if !soft_cap.met => soft_cap.min_sold,
+            _ => {
+                self.released_proceeds += price;
+                return;
+            },
+        };
+
+        if price > 0 {
+            self.paid_by_token.insert(&token_id, &price);
+            self.escrowed_proceeds += price;
+        }
+
+        if self.internal_public_sold() >= min_sold {
+            self.soft_cap.as_mut().unwrap().met = true;
+
+            log_event("soft_cap_met", json!({ "released": U128(self.escrowed_proceeds) }));
+
+            self.released_proceeds += self.escrowed_proceeds;
+            self.escrowed_proceeds = 0;
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::*;
+use crate::events::log_event;
+use ino_common::storage::{assert_unregister_not_forced, storage_deposit_kept};
+use near_contract_standards::storage_management::{
+    StorageBalance, StorageBalanceBounds, StorageManagement,
+};
+use near_sdk::assert_one_yocto;
+use near_sdk::serde_json::json;
+
+// Registration entry: record overhead, prefixed account key and the stored balance
+const ACCOUNT_STORAGE_BYTES: u64 = 40 + 1 + 4 + 64 + 16 + 8;
+// Ownership records of a token: the owner entry and the two records of the holder's token set
+// entry, with the longest token and account ids. Tokens are charged what minting them measured,
+// this bounds quotes and stands in for tokens minted before that was recorded.
+pub(crate) const TOKEN_STORAGE_BYTES: u64 = 420;
+// Token metadata record besides its value: overhead and the prefixed key of the longest id
+const TOKEN_METADATA_RECORD_BYTES: u64 = 40 + 1 + 4 + 39;
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MintCost {
+    pub price: U128,
+    // Part of the receiver's storage for the token that their balance does not cover yet
+    pub storage: U128,
+    pub total: U128,
+    // Gated mints are answered in a callback, so a batch of them can't fail or succeed as a whole
+    pub gated: bool,
+}
+
+#[near_bindgen]
+impl StorageManagement for Contract {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<ValidAccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id: AccountId = account_id
+            .map(|account_id| account_id.into())
+            .unwrap_or_else(env::predecessor_account_id);
+
+        let deposit = storage_deposit_kept(
+            amount,
+            self.storage_accounts.get(&account_id).is_some(),
+            registration_only.unwrap_or(false),
+            self.storage_balance_bounds().min.0,
+        );
+
+        self.internal_deposit_for_storage(&account_id, deposit);
+
+        log_event("storage_deposit", json!({ "account_id": account_id, "amount": U128(deposit) }));
+
+        self.internal_storage_account(&account_id).to_storage_balance()
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let mut account = self.internal_storage_account(&account_id);
+
+        let amount = account.withdraw(amount);
+        self.storage_deposits -= amount;
+        self.storage_accounts.insert(&account_id, &account);
+
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+
+        log_event("storage_withdraw", json!({ "account_id": account_id, "amount": U128(amount) }));
+
+        account.to_storage_balance()
+    }
+
+    // Tokens can't be dropped on behalf of their holder, so `force` is not supported and
+    // accounts can only leave once they hold no tokens and approvals
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        assert_unregister_not_forced(force);
+
+        let account_id = env::predecessor_account_id();
+        let account = match self.storage_accounts.get(&account_id) {
+            None => return false,
+            Some(account) => account,
+        };
+
+        account.assert_unregisterable(ACCOUNT_STORAGE_BYTES);
+
+        self.storage_accounts.remove(&account_id);
+        self.storage_deposits -= account.deposit;
+        self.storage_used_bytes -= account.used_bytes;
+        Promise::new(account_id.clone()).transfer(account.deposit);
+
+        log_event("storage_unregister", json!({ "account_id": account_id }));
+
+        true
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(Balance::from(ACCOUNT_STORAGE_BYTES) * env::storage_byte_cost()),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: ValidAccountId) -> Option<StorageBalance> {
+        self.storage_accounts
+            .get(account_id.as_ref())
+            .map(|account| account.to_storage_balance())
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Deposit `quantity` (1 by default) `nft_mint` calls need at most for `receiver_id` right now,
+    // with `storage` deposited up front when minting several. Storage a token ends up not using is refunded.
+    pub fn get_mint_cost(&self, receiver_id: ValidAccountId, quantity: Option<u32>) -> MintCost {
+        let quantity = quantity.unwrap_or(1);
+
+        let price = (self.mint_price * MULTIPLYER)
+            .checked_mul(Balance::from(quantity))
+            .unwrap_or_else(|| mint_cost_overflow());
+        let bytes = self
+            .internal_token_storage_estimate()
+            .checked_mul(u64::from(quantity))
+            .unwrap_or_else(|| mint_cost_overflow());
+        let storage = self.internal_storage_shortfall(receiver_id.as_ref(), bytes);
+
+        MintCost {
+            price: U128(price),
+            storage: U128(storage),
+            total: U128(price.checked_add(storage).unwrap_or_else(|| mint_cost_overflow())),
+            gated: self.mint_gate.is_some(),
+        }
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_storage_account(&self, account_id: &AccountId) -> StorageAccount {
+        self.storage_accounts
+            .get(account_id)
+            .unwrap_or_else(|| ContractError::StorageNotRegistered.panic_with(account_id))
+    }
+
+    // Amount `account_id` still has to deposit before `bytes` more can be charged, registration included
+    pub(crate) fn internal_storage_shortfall(&self, account_id: &AccountId, bytes: u64) -> Balance {
+        let cost = Balance::from(bytes) * env::storage_byte_cost();
+
+        match self.storage_accounts.get(account_id) {
+            None => self.storage_balance_bounds().min.0 + cost,
+            Some(account) => (account.used() + cost).saturating_sub(account.deposit),
+        }
+    }
+
+    // Credits `amount` to the account, registering it first when it is new
+    pub(crate) fn internal_deposit_for_storage(&mut self, account_id: &AccountId, amount: Balance) {
+        let account = match self.storage_accounts.get(account_id) {
+            Some(mut account) => {
+                account.deposit += amount;
+                account
+            },
+            None => {
+                self.storage_used_bytes += ACCOUNT_STORAGE_BYTES;
+                StorageAccount { deposit: amount, used_bytes: ACCOUNT_STORAGE_BYTES }
+            },
+        };
+
+        self.storage_deposits += amount;
+        self.storage_accounts.insert(account_id, &account);
+    }
+
+    // Upper bound of what minting one more token will charge its holder: ownership records plus
+    // the largest metadata the template and uploads can produce
+    pub(crate) fn internal_token_storage_estimate(&self) -> u64 {
+        let last_id = self.total_count.saturating_sub(1);
+        let rendered = self.metadata_template.render(&self.metadata.get().unwrap(), last_id);
+
+        TOKEN_STORAGE_BYTES
+            + TOKEN_METADATA_RECORD_BYTES
+            + rendered.try_to_vec().unwrap().len() as u64
+            + self.max_upload_bytes
+    }
+
+    // What the token's holder is charged for it
+    pub(crate) fn internal_token_storage_bytes(&self, token_id: &TokenId) -> u64 {
+        token_id
+            .parse::<u128>()
+            .ok()
+            .and_then(|id| self.storage_bytes_by_token.get(&id))
+            .unwrap_or(TOKEN_STORAGE_BYTES)
+    }
+
+    // Takes what the receiver's balance lacks for the token's `bytes` out of the deposit left after
+    // `price`, charges the token and refunds whatever remains to the payer
+    pub(crate) fn internal_fund_token_storage(
+        &mut self,
+        receiver_id: &AccountId,
+        payer_id: &AccountId,
+        deposit: Balance,
+        price: Balance,
+        bytes: u64,
+    ) {
+        let deposit = deposit - price;
+        let shortfall = self.internal_storage_shortfall(receiver_id, bytes);
+
+        if deposit < shortfall {
+            ContractError::InsufficientStorageDeposit.panic_with(format!("{} yoctoNEAR required", price + shortfall));
+        }
+
+        self.internal_deposit_for_storage(receiver_id, shortfall);
+        self.internal_charge_storage(receiver_id, bytes, false);
+
+        let refund = deposit - shortfall;
+        if refund > 1 {
+            Promise::new(payer_id.clone()).transfer(refund);
+        }
+    }
+
+    // Callbacks can't fail anymore, so they pass `allow_overdraw` and the account simply ends up
+    // with less available than it uses until it deposits again
+    pub(crate) fn internal_charge_storage(&mut self, account_id: &AccountId, bytes: u64, allow_overdraw: bool) {
+        let mut account = match self.storage_accounts.get(account_id) {
+            Some(account) => account,
+            None if allow_overdraw => {
+                self.internal_deposit_for_storage(account_id, 0);
+                self.internal_storage_account(account_id)
+            },
+            None => ContractError::StorageNotRegistered.panic_with(account_id),
+        };
+        account.charge(bytes, allow_overdraw);
+
+        self.storage_used_bytes += bytes;
+        self.storage_accounts.insert(account_id, &account);
+    }
+
+    pub(crate) fn internal_release_storage(&mut self, account_id: &AccountId, bytes: u64) {
+        if let Some(mut account) = self.storage_accounts.get(account_id) {
+            self.storage_used_bytes -= account.release(bytes, ACCOUNT_STORAGE_BYTES);
+            self.storage_accounts.insert(account_id, &account);
+        }
+    }
+
+    // Moves the holder's share of a token to its new owner and frees the approvals it dropped
+    pub(crate) fn internal_move_token_storage(
+        &mut self,
+        token_id: &TokenId,
+        from: &AccountId,
+        to: &AccountId,
+        approved_account_ids: Option<&HashMap<AccountId, u64>>,
+        allow_overdraw: bool,
+    ) {
+        let bytes = self.internal_token_storage_bytes(token_id);
+
+        self.internal_release_storage(from, bytes + approvals_storage_bytes(approved_account_ids));
+        self.internal_charge_storage(to, bytes, allow_overdraw);
+    }
+
+    // Deposits held for accounts beyond what their storage uses, they are not part of the proceeds
+    pub(crate) fn internal_unused_storage_deposits(&self) -> Balance {
+        self.storage_deposits
+            .saturating_sub(Balance::from(self.storage_used_bytes) * env::storage_byte_cost())
+    }
+}
+
+fn mint_cost_overflow() -> ! {
+    ContractError::InvalidQuantity.panic_with("the mint cost overflows")
+}
+
+// Same measure the approval standard uses for an approved account
+pub(crate) fn bytes_for_approved_account_id(account_id: &AccountId) -> u64 {
+    account_id.len() as u64 + 4 + 8
+}
+
+pub(crate) fn approvals_storage_bytes(approved_account_ids: Option<&HashMap<AccountId, u64>>) -> u64 {
+    approved_account_ids
+        .map(|approvals| approvals.keys().map(bytes_for_approved_account_id).sum())
+        .unwrap_or(0)
+}
@@ -0,0 +1,91 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json, Value};
+
+const NAME_PLACEHOLDER: &str = "{name}";
+const ID_PLACEHOLDER: &str = "{id}";
+
+// Describes how token metadata is built when `nft_mint` picks an id.
+// `title` and `description` may contain `{name}` (collection name) and `{id}` (token id).
+// Media and reference are `{id}.{media_extension}` and `{id}.json`, relative to the contract's
+// `base_uri` as NEP-177 allows, so moving the files only takes `set_base_uri`.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MetadataTemplate {
+    pub title: String,
+    pub description: String,
+    pub media_extension: String,
+    pub extra: Option<String>,
+}
+
+impl Default for MetadataTemplate {
+    fn default() -> Self {
+        Self {
+            title: "{name} #{id}".to_string(),
+            description: "{name}, minted by Dao Nation".to_string(),
+            media_extension: "png".to_string(),
+            extra: None,
+        }
+    }
+}
+
+impl MetadataTemplate {
+    pub fn assert_valid(&self) {
+        if self.title.is_empty() {
+            ContractError::InvalidTemplate.panic_with("title should not be empty");
+        }
+        if self.media_extension.is_empty() || self.media_extension.contains('/') || self.media_extension.starts_with('.') {
+            ContractError::InvalidTemplate.panic_with("media extension should be a bare extension like png");
+        }
+        if let Some(extra) = &self.extra {
+            if serde_json::from_str::<Value>(extra).is_err() {
+                ContractError::InvalidTemplate.panic_with("extra should be valid JSON");
+            }
+        }
+    }
+
+    pub fn render(&self, metadata: &NFTContractMetadata, token_id: u128) -> TokenMetadata {
+        let fill = |pattern: &str| {
+            pattern
+                .replace(NAME_PLACEHOLDER, &metadata.name)
+                .replace(ID_PLACEHOLDER, &token_id.to_string())
+        };
+
+        TokenMetadata {
+            title: Some(fill(&self.title)),
+            description: Some(fill(&self.description)),
+            media: Some(format!("{}.{}", token_id, self.media_extension)),
+            media_hash: None,
+            copies: Some(1),
+            issued_at: None,
+            expires_at: None,
+            starts_at: None,
+            updated_at: None,
+            extra: self.extra.clone(),
+            reference: Some(format!("{}.json", token_id)),
+            reference_hash: None,
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // The template can only change before the first token is minted so every token shares it
+    pub fn set_metadata_template(&mut self, template: MetadataTemplate) {
+        self.assert_owner();
+        self.assert_metadata_not_frozen();
+
+        if self.minted_count > 0 {
+            ContractError::MetadataLocked.panic();
+        }
+
+        template.assert_valid();
+        self.metadata_template = template;
+
+        log_event("metadata_template_updated", json!({ "template": self.metadata_template }));
+    }
+
+    pub fn get_metadata_template(&self) -> MetadataTemplate {
+        self.metadata_template.clone()
+    }
+}
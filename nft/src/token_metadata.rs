@@ -0,0 +1,157 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::{self, json, Value};
+
+const HASH_LENGTH: usize = 32;
+
+// Per-token data uploaded by the owner ahead of the sale, merged over the template at mint time
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadataUpload {
+    pub token_id: U128,
+    pub title: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub reference_hash: Option<Base64VecU8>,
+    pub extra: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<TokenAttribute>,
+}
+
+impl TokenMetadataUpload {
+    fn assert_valid(&self, total_count: u128) {
+        if self.token_id.0 >= total_count {
+            ContractError::InvalidTokenMetadata.panic_with(format!("token id {} is out of the collection", self.token_id.0));
+        }
+        if let Some(hash) = &self.media_hash {
+            if hash.0.len() != HASH_LENGTH {
+                ContractError::InvalidTokenMetadata.panic_with("media hash should be a 32 bytes sha256");
+            }
+        }
+        if let Some(hash) = &self.reference_hash {
+            if hash.0.len() != HASH_LENGTH {
+                ContractError::InvalidTokenMetadata.panic_with("reference hash should be a 32 bytes sha256");
+            }
+        }
+        if let Some(extra) = &self.extra {
+            if serde_json::from_str::<Value>(extra).is_err() {
+                ContractError::InvalidTokenMetadata.panic_with("extra should be valid JSON");
+            }
+        }
+        for attribute in self.attributes.iter() {
+            attribute.assert_valid();
+        }
+    }
+}
+
+// How an upload is kept in state
+#[derive(BorshDeserialize, BorshSerialize)]
+pub(crate) struct StoredTokenMetadata {
+    pub token_id: u128,
+    pub title: Option<String>,
+    pub media_hash: Option<Base64VecU8>,
+    pub reference_hash: Option<Base64VecU8>,
+    pub extra: Option<String>,
+    pub attributes: Vec<TokenAttribute>,
+}
+
+impl From<&TokenMetadataUpload> for StoredTokenMetadata {
+    fn from(upload: &TokenMetadataUpload) -> Self {
+        Self {
+            token_id: upload.token_id.0,
+            title: upload.title.clone(),
+            media_hash: upload.media_hash.clone(),
+            reference_hash: upload.reference_hash.clone(),
+            extra: upload.extra.clone(),
+            attributes: upload.attributes.clone(),
+        }
+    }
+}
+
+impl From<StoredTokenMetadata> for TokenMetadataUpload {
+    fn from(stored: StoredTokenMetadata) -> Self {
+        Self {
+            token_id: U128(stored.token_id),
+            title: stored.title,
+            media_hash: stored.media_hash,
+            reference_hash: stored.reference_hash,
+            extra: stored.extra,
+            attributes: stored.attributes,
+        }
+    }
+}
+
+impl StoredTokenMetadata {
+    pub(crate) fn apply(&self, metadata: &mut TokenMetadata) {
+        if self.title.is_some() {
+            metadata.title = self.title.clone();
+        }
+        metadata.media_hash = self.media_hash.clone();
+        metadata.reference_hash = self.reference_hash.clone();
+        if self.extra.is_some() {
+            metadata.extra = self.extra.clone();
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadataProgress {
+    pub loaded: U128,
+    pub total: U128,
+}
+
+#[near_bindgen]
+impl Contract {
+    // Uploads can be split over several calls, re-uploading an id replaces its previous entry
+    #[payable]
+    pub fn set_token_metadata(&mut self, entries: Vec<TokenMetadataUpload>) {
+        self.assert_owner();
+        self.assert_metadata_not_frozen();
+
+        if self.minted_count > 0 {
+            ContractError::MetadataLocked.panic();
+        }
+
+        let initial_storage_usage = env::storage_usage();
+
+        for entry in entries.iter() {
+            entry.assert_valid(self.total_count);
+
+            let stored = StoredTokenMetadata::from(entry);
+            self.max_upload_bytes = self.max_upload_bytes.max(stored.try_to_vec().unwrap().len() as u64);
+
+            match self.metadata_uploads.insert(&entry.token_id.0, &stored) {
+                None => self.loaded_metadata_count += 1,
+                Some(previous) => self.internal_remove_attributes(previous.token_id, &previous.attributes),
+            }
+            self.internal_add_attributes(entry.token_id.0, &entry.attributes);
+        }
+
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        log_event("token_metadata_loaded", json!({ "count": entries.len(), "loaded": U128(self.loaded_metadata_count) }));
+    }
+
+    pub fn get_token_metadata_upload(&self, token_id: U128) -> Option<TokenMetadataUpload> {
+        self.metadata_uploads.get(&token_id.0).map(TokenMetadataUpload::from)
+    }
+
+    pub fn get_token_metadata_progress(&self) -> TokenMetadataProgress {
+        TokenMetadataProgress {
+            loaded: U128(self.loaded_metadata_count),
+            total: U128(self.total_count),
+        }
+    }
+}
+
+impl Contract {
+    // Collections without uploads rely on the template alone, otherwise every id must be loaded
+    pub(crate) fn assert_token_metadata_ready(&self) {
+        if self.loaded_metadata_count > 0 && self.loaded_metadata_count < self.total_count {
+            ContractError::MetadataIncomplete.panic_with(format!(
+                "{} of {} ids are loaded",
+                self.loaded_metadata_count, self.total_count
+            ));
+        }
+    }
+}
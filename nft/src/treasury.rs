@@ -0,0 +1,41 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[near_bindgen]
+impl Contract {
+    // Sends collected mint proceeds out of the contract, keeping enough balance to cover storage,
+    // the storage deposits holders have not used yet, proceeds escrowed under a soft cap and
+    // proceeds still owed to a vesting beneficiary and raffle entry deposits
+    pub fn withdraw(&mut self, amount: U128, receiver_id: Option<ValidAccountId>) -> Promise {
+        self.assert_role(Role::Treasurer);
+
+        let amount: Balance = amount.into();
+        let receiver_id: AccountId = match receiver_id {
+            None => env::predecessor_account_id(),
+            Some(account_id) => account_id.into(),
+        };
+
+        let available = self.get_withdrawable_balance().0;
+        if amount > available {
+            ContractError::WithdrawTooLarge.panic_with(format!("{} yoctoNEAR available", available));
+        }
+
+        log_event("withdraw", json!({ "receiver_id": receiver_id, "amount": U128(amount), "by": env::predecessor_account_id() }));
+
+        Promise::new(receiver_id).transfer(amount)
+    }
+
+    pub fn get_withdrawable_balance(&self) -> U128 {
+        let locked_for_storage = env::storage_byte_cost() * Balance::from(env::storage_usage());
+
+        U128(
+            env::account_balance()
+                .saturating_sub(locked_for_storage)
+                .saturating_sub(self.internal_unused_storage_deposits())
+                .saturating_sub(self.escrowed_proceeds)
+                .saturating_sub(self.internal_locked_proceeds())
+                .saturating_sub(self.internal_raffle_deposits()),
+        )
+    }
+}
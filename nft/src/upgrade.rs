@@ -0,0 +1,351 @@
+use crate::*;
+use crate::events::log_event;
+use ino_common::state;
+use near_sdk::serde_json::json;
+use near_sdk::Gas;
+
+// Bumped with every change to the `Contract` layout. Each released layout keeps a struct and a
+// decoder below so that `migrate` works from any of them.
+pub(crate) const CURRENT_STATE_VERSION: u16 = 3;
+
+// Gas kept back by `upgrade` itself, everything else is handed to `migrate`
+const GAS_FOR_UPGRADE: Gas = 10_000_000_000_000;
+// Ids moved per `migrate_minted_ids` call unless asked otherwise
+const DEFAULT_MINTED_IDS_BATCH: u64 = 500;
+
+// Layout of `Contract` deployed before state versioning existed
+#[derive(BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub(crate) struct ContractV1 {
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    total_count: u128,
+    minted_count: u128,
+    is_minted_by_id: UnorderedMap<u128, bool>,
+    mint_price: Balance,
+}
+
+// Layout of `Contract` at state version 2, when pausing, roles and two-step ownership were added
+#[derive(BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub(crate) struct ContractV2 {
+    tokens: NonFungibleToken,
+    metadata: LazyOption<NFTContractMetadata>,
+    total_count: u128,
+    minted_count: u128,
+    is_minted_by_id: UnorderedMap<u128, bool>,
+    mint_price: Balance,
+    mint_paused: bool,
+    transfers_paused: bool,
+    reserved_minted_count: u128,
+    roles_by_account: UnorderedMap<AccountId, Vec<Role>>,
+    pending_owner: Option<AccountId>,
+}
+
+impl From<ContractV1> for ContractV2 {
+    fn from(old: ContractV1) -> Self {
+        Self {
+            tokens: old.tokens,
+            metadata: old.metadata,
+            total_count: old.total_count,
+            minted_count: old.minted_count,
+            is_minted_by_id: old.is_minted_by_id,
+            mint_price: old.mint_price,
+            mint_paused: false,
+            transfers_paused: false,
+            reserved_minted_count: 0,
+            roles_by_account: UnorderedMap::new(StorageKey::Roles),
+            pending_owner: None,
+        }
+    }
+}
+
+pub(crate) enum VersionedContract {
+    V1(ContractV1),
+    V2(ContractV2),
+    Current(Contract),
+}
+
+impl VersionedContract {
+    pub(crate) fn read() -> Self {
+        let (version, data) = state::read_state();
+
+        match version {
+            1 => VersionedContract::V1(state::decode_state::<ContractV1>(&data, version)),
+            2 => VersionedContract::V2(state::decode_state::<ContractV2>(&data, version)),
+            CURRENT_STATE_VERSION => VersionedContract::Current(state::decode_state::<Contract>(&data, version)),
+            _ => ContractError::UnknownStateVersion.panic_with(version),
+        }
+    }
+
+    pub(crate) fn into_current(self) -> Contract {
+        match self {
+            VersionedContract::V1(old) => VersionedContract::V2(old.into()).into_current(),
+            VersionedContract::V2(old) => Contract {
+                minted_ids: MintedBitmap::new(StorageKey::MintedIds),
+                tokens: old.tokens,
+                metadata: old.metadata,
+                total_count: old.total_count,
+                minted_count: old.minted_count,
+                mint_price: old.mint_price,
+                mint_paused: old.mint_paused,
+                transfers_paused: old.transfers_paused,
+                reserved_minted_count: old.reserved_minted_count,
+                roles_by_account: old.roles_by_account,
+                pending_owner: old.pending_owner,
+                metadata_template: MetadataTemplate::default(),
+                metadata_uploads: LookupMap::new(StorageKey::MetadataUploads),
+                loaded_metadata_count: 0,
+                trait_counts: UnorderedMap::new(StorageKey::TraitCounts),
+                tokens_by_trait: LookupMap::new(StorageKey::TokensByTrait),
+                metadata_provenance: None,
+                burned_count: 0,
+                mint_counts_by_account: LookupMap::new(StorageKey::MintCountsByAccount),
+                storage_accounts: LookupMap::new(StorageKey::StorageAccounts),
+                storage_deposits: 0,
+                storage_used_bytes: 0,
+                launchpad: None,
+                soft_cap: None,
+                escrowed_proceeds: 0,
+                paid_by_token: LookupMap::new(StorageKey::PaidByToken),
+                released_proceeds: 0,
+                vesting: None,
+                raffle: None,
+                raffle_entries: UnorderedMap::new(StorageKey::RaffleEntries),
+                mint_gate: None,
+                legacy_minted_ids: legacy_minted_ids(old.is_minted_by_id),
+                metadata_freeze: None,
+                storage_bytes_by_token: LookupMap::new(StorageKey::StorageBytesByToken),
+                max_upload_bytes: 0,
+            },
+            VersionedContract::Current(contract) => contract,
+        }
+    }
+}
+
+// V1 and V2 kept one map entry per minted id. Moving them all in `migrate` has no gas bound, so the map
+// is kept and emptied into the bitmap by `migrate_minted_ids`.
+fn legacy_minted_ids(is_minted_by_id: UnorderedMap<u128, bool>) -> Option<UnorderedMap<u128, bool>> {
+    if is_minted_by_id.is_empty() {
+        None
+    } else {
+        Some(is_minted_by_id)
+    }
+}
+
+pub(crate) fn write_state_version() {
+    state::write_state_version(CURRENT_STATE_VERSION);
+}
+
+#[near_bindgen]
+impl Contract {
+    #[init(ignore_state)]
+    #[private]
+    pub fn migrate() -> Self {
+        let contract = VersionedContract::read().into_current();
+        write_state_version();
+
+        log_event("state_migrated", json!({ "version": CURRENT_STATE_VERSION }));
+
+        contract
+    }
+
+    // Deploys the wasm passed as raw input over this account and runs `migrate` on it
+    pub fn upgrade(&self) -> Promise {
+        self.assert_owner();
+
+        state::upgrade_self(GAS_FOR_UPGRADE)
+    }
+
+    // Moves up to `limit` V1 minted ids into the bitmap and returns how many are left. Minting
+    // stays closed until none are.
+    pub fn migrate_minted_ids(&mut self, limit: Option<U64>) -> U64 {
+        let legacy = self
+            .legacy_minted_ids
+            .as_mut()
+            .unwrap_or_else(|| ContractError::InvalidState.panic_with("no minted ids to migrate"));
+
+        let limit = limit.map_or(DEFAULT_MINTED_IDS_BATCH, |limit| limit.0);
+        let ids: Vec<u128> = legacy.keys().take(limit as usize).collect();
+        for id in &ids {
+            self.minted_ids.insert(*id);
+            legacy.remove(id);
+        }
+
+        let remaining = legacy.len();
+        if remaining == 0 {
+            self.legacy_minted_ids = None;
+        }
+
+        log_event("minted_ids_migrated", json!({ "migrated": U64(ids.len() as u64), "remaining": U64(remaining) }));
+
+        U64(remaining)
+    }
+
+    pub fn get_state_version(&self) -> u16 {
+        CURRENT_STATE_VERSION
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_is_minted(&self, id: u128) -> bool {
+        self.minted_ids.contains(id)
+            || self.legacy_minted_ids.as_ref().map_or(false, |legacy| legacy.get(&id).is_some())
+    }
+
+    pub(crate) fn assert_minted_ids_migrated(&self) {
+        if self.legacy_minted_ids.is_some() {
+            ContractError::MigrationPending.panic_with("call migrate_minted_ids first");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, VMContextBuilder};
+    use near_sdk::{testing_env, MockedBlockchain};
+
+    fn contract_metadata() -> NFTContractMetadata {
+        NFTContractMetadata {
+            spec: NFT_METADATA_SPEC.to_string(),
+            name: "Test".to_string(),
+            symbol: "TEST".to_string(),
+            icon: None,
+            base_uri: None,
+            reference: None,
+            reference_hash: None,
+        }
+    }
+
+    // Writes V1 state with `minted` ids taken, the way it was left before state versioning
+    fn write_v1_state(total_count: u128, minted: &[u128]) {
+        testing_env!(VMContextBuilder::new().build());
+
+        let mut is_minted_by_id = UnorderedMap::new(b"is_minted_by_id".to_vec());
+        for id in minted {
+            is_minted_by_id.insert(id, &true);
+        }
+
+        env::state_write(&ContractV1 {
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                accounts(0),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&contract_metadata())),
+            total_count,
+            minted_count: minted.len() as u128,
+            is_minted_by_id,
+            mint_price: 1,
+        });
+    }
+
+    #[test]
+    fn migrate_v1_moves_minted_ids_in_batches() {
+        write_v1_state(300, &[1, 4, 7, 130, 299]);
+
+        let mut contract = Contract::migrate();
+        assert_eq!(state::read_state().0, CURRENT_STATE_VERSION);
+        assert_eq!(contract.get_minted().0, 5);
+
+        // answered from the V1 map until the ids are moved
+        assert!(contract.is_minted("130".to_string()));
+        assert!(!contract.is_minted("2".to_string()));
+
+        assert_eq!(contract.migrate_minted_ids(Some(U64(2))).0, 3);
+        assert_eq!(contract.migrate_minted_ids(Some(U64(2))).0, 1);
+        assert!(contract.legacy_minted_ids.is_some());
+        assert_eq!(contract.migrate_minted_ids(None).0, 0);
+        assert!(contract.legacy_minted_ids.is_none());
+
+        for id in &[1, 4, 7, 130, 299] {
+            assert!(contract.minted_ids.contains(*id));
+            assert!(contract.is_minted(id.to_string()));
+        }
+        assert!(!contract.is_minted("2".to_string()));
+        contract.assert_minted_ids_migrated();
+    }
+
+    #[test]
+    #[should_panic(expected = "E606")]
+    fn migrate_v1_blocks_minting_until_ids_are_moved() {
+        write_v1_state(10, &[3]);
+
+        Contract::migrate().assert_minted_ids_migrated();
+    }
+
+    #[test]
+    fn migrate_v1_without_minted_ids_needs_no_batches() {
+        write_v1_state(10, &[]);
+
+        let contract = Contract::migrate();
+        assert!(contract.legacy_minted_ids.is_none());
+        contract.assert_minted_ids_migrated();
+    }
+
+    #[test]
+    fn migrate_v2_keeps_pause_flags_roles_and_pending_owner() {
+        testing_env!(VMContextBuilder::new().build());
+
+        let mut is_minted_by_id = UnorderedMap::new(b"is_minted_by_id".to_vec());
+        is_minted_by_id.insert(&2, &true);
+        let mut roles_by_account = UnorderedMap::new(StorageKey::Roles);
+        roles_by_account.insert(&accounts(1).into(), &vec![Role::Minter]);
+
+        env::state_write(&ContractV2 {
+            tokens: NonFungibleToken::new(
+                StorageKey::NonFungibleToken,
+                accounts(0),
+                Some(StorageKey::TokenMetadata),
+                Some(StorageKey::Enumeration),
+                Some(StorageKey::Approval),
+            ),
+            metadata: LazyOption::new(StorageKey::Metadata, Some(&contract_metadata())),
+            total_count: 10,
+            minted_count: 1,
+            is_minted_by_id,
+            mint_price: 1,
+            mint_paused: true,
+            transfers_paused: false,
+            reserved_minted_count: 1,
+            roles_by_account,
+            pending_owner: Some(accounts(2).into()),
+        });
+        state::write_state_version(2);
+
+        let mut contract = Contract::migrate();
+        assert_eq!(state::read_state().0, CURRENT_STATE_VERSION);
+        assert!(contract.get_pause_status().mint_paused);
+        assert!(contract.has_role(accounts(1), Role::Minter));
+        assert_eq!(contract.get_pending_owner(), Some(accounts(2).into()));
+        assert_eq!(contract.reserved_minted_count, 1);
+
+        assert!(contract.is_minted("2".to_string()));
+        assert_eq!(contract.migrate_minted_ids(None).0, 0);
+        assert!(contract.minted_ids.contains(2));
+    }
+
+    #[test]
+    fn migrate_current_state_keeps_it() {
+        testing_env!(VMContextBuilder::new().build());
+        let mut contract = Contract::new(accounts(0), contract_metadata(), U128(1), U128(10), None);
+        contract.minted_ids.insert(5);
+        env::state_write(&contract);
+
+        let contract = Contract::migrate();
+        assert!(contract.is_minted("5".to_string()));
+        assert_eq!(contract.total_count, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "E603")]
+    fn migrate_rejects_unknown_state_version() {
+        write_v1_state(10, &[]);
+        state::write_state_version(CURRENT_STATE_VERSION + 1);
+
+        Contract::migrate();
+    }
+}
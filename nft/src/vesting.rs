@@ -0,0 +1,159 @@
+use crate::*;
+use crate::events::log_event;
+use near_sdk::serde_json::json;
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Vesting {
+    pub beneficiary_id: AccountId,
+    // Timestamps and durations in nanoseconds
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+    pub claimed: Balance,
+    // Set while frozen to what had vested at that time, the cap on claims until it is lifted
+    pub frozen_vested: Option<Balance>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VestingView {
+    pub beneficiary_id: AccountId,
+    pub start: U64,
+    pub cliff: U64,
+    pub duration: U64,
+    // Released proceeds the schedule applies to, growing with each mint
+    pub total: U128,
+    pub vested: U128,
+    pub claimed: U128,
+    pub claimable: U128,
+    pub remaining: U128,
+    pub frozen: bool,
+}
+
+impl Vesting {
+    // Nothing before the cliff, then linear until `duration` has passed since `start`.
+    // A cliff as long as the duration releases everything at once.
+    fn vested(&self, total: Balance, now: u64) -> Balance {
+        if let Some(frozen_vested) = self.frozen_vested {
+            return frozen_vested;
+        }
+
+        let elapsed = now.saturating_sub(self.start);
+
+        if elapsed < self.cliff {
+            0
+        } else if elapsed >= self.duration {
+            total
+        } else {
+            // split to keep `total * elapsed` from overflowing
+            let (elapsed, duration) = (Balance::from(elapsed), Balance::from(self.duration));
+            total / duration * elapsed + total % duration * elapsed / duration
+        }
+    }
+}
+
+#[near_bindgen]
+impl Contract {
+    // Proceeds then only leave the contract through `claim_vested`
+    pub fn set_vesting(&mut self, beneficiary_id: ValidAccountId, start: U64, cliff: U64, duration: U64) {
+        self.assert_owner();
+
+        if self.minted_count > 0 {
+            ContractError::SaleLocked.panic();
+        }
+        if duration.0 == 0 || cliff.0 > duration.0 {
+            ContractError::InvalidVesting.panic();
+        }
+
+        self.vesting = Some(Vesting {
+            beneficiary_id: beneficiary_id.into(),
+            start: start.0,
+            cliff: cliff.0,
+            duration: duration.0,
+            claimed: 0,
+            frozen_vested: None,
+        });
+
+        log_event("vesting_set", json!({ "beneficiary_id": self.vesting.as_ref().unwrap().beneficiary_id, "start": start, "cliff": cliff, "duration": duration }));
+    }
+
+    pub fn claim_vested(&mut self) -> Promise {
+        let released_proceeds = self.released_proceeds;
+        let vesting = self.vesting.as_mut().unwrap_or_else(|| ContractError::NoVesting.panic());
+
+        if env::predecessor_account_id() != vesting.beneficiary_id {
+            ContractError::NotBeneficiary.panic();
+        }
+        let amount = vesting.vested(released_proceeds, env::block_timestamp()) - vesting.claimed;
+        if amount == 0 {
+            ContractError::NothingToClaim.panic();
+        }
+        vesting.claimed += amount;
+
+        log_event("vested_claimed", json!({ "beneficiary_id": vesting.beneficiary_id, "amount": U128(amount) }));
+
+        Promise::new(vesting.beneficiary_id.clone()).transfer(amount)
+    }
+
+    // Used when a collection gets flagged. The INO registry it reports to or an admin can freeze,
+    // but only the registry can lift a freeze, so the owner can't undo a curator's decision.
+    // Without a registry nobody could lift it, so freezing needs one.
+    pub fn set_vesting_frozen(&mut self, frozen: bool) {
+        if self.launchpad.is_none() {
+            ContractError::NoLaunchpad.panic();
+        }
+        let is_launchpad = self.launchpad.as_ref() == Some(&env::predecessor_account_id());
+        if !frozen && !is_launchpad {
+            ContractError::NotLaunchpad.panic();
+        }
+        if !is_launchpad {
+            self.assert_role(Role::Admin);
+        }
+
+        let released_proceeds = self.released_proceeds;
+        let vesting = self.vesting.as_mut().unwrap_or_else(|| ContractError::NoVesting.panic());
+        vesting.frozen_vested = if frozen {
+            // proceeds of later mints stay locked until the freeze is lifted
+            Some(vesting.frozen_vested.unwrap_or_else(|| vesting.vested(released_proceeds, env::block_timestamp())))
+        } else {
+            None
+        };
+
+        log_event("vesting_frozen", json!({ "frozen": frozen, "by": env::predecessor_account_id() }));
+    }
+
+    pub fn get_vesting(&self) -> Option<VestingView> {
+        self.vesting.as_ref().map(|vesting| {
+            let vested = vesting.vested(self.released_proceeds, env::block_timestamp());
+
+            VestingView {
+                beneficiary_id: vesting.beneficiary_id.clone(),
+                start: U64(vesting.start),
+                cliff: U64(vesting.cliff),
+                duration: U64(vesting.duration),
+                total: U128(self.released_proceeds),
+                vested: U128(vested),
+                claimed: U128(vesting.claimed),
+                claimable: U128(vested - vesting.claimed),
+                remaining: U128(self.released_proceeds - vesting.claimed),
+                frozen: vesting.frozen_vested.is_some(),
+            }
+        })
+    }
+}
+
+impl Contract {
+    // Proceeds held for the vesting beneficiary, kept out of `withdraw`
+    pub(crate) fn internal_locked_proceeds(&self) -> Balance {
+        match &self.vesting {
+            Some(vesting) => self.released_proceeds - vesting.claimed,
+            None => 0,
+        }
+    }
+
+    pub(crate) fn assert_vesting_not_frozen(&self) {
+        if self.vesting.as_ref().map_or(false, |vesting| vesting.frozen_vested.is_some()) {
+            ContractError::VestingFrozen.panic();
+        }
+    }
+}
@@ -0,0 +1,125 @@
+/*!
+A stub of the Sputnik DAO proposal flow for simulation testing the INO DAO integration.
+Proposals take a fixed bond, approving or rejecting one returns the bond to its proposer and
+removing one keeps it, like Sputnik does.
+*/
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::Vector;
+use near_sdk::json_types::{Base64VecU8, U128, U64};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{env, near_bindgen, AccountId, Balance, PanicOnDefault, Promise};
+
+near_sdk::setup_alloc!();
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ActionCall {
+    pub method_name: String,
+    pub args: Base64VecU8,
+    pub deposit: U128,
+    pub gas: U64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalKind {
+    FunctionCall { receiver_id: AccountId, actions: Vec<ActionCall> },
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalInput {
+    pub description: String,
+    pub kind: ProposalKind,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ProposalStatus {
+    InProgress,
+    Approved,
+    Rejected,
+    Removed,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposer: AccountId,
+    pub description: String,
+    pub kind: ProposalKind,
+    pub status: ProposalStatus,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalOutput {
+    pub id: u64,
+    #[serde(flatten)]
+    pub proposal: Proposal,
+}
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct MockDao {
+    proposal_bond: Balance,
+    proposals: Vector<Proposal>,
+}
+
+#[near_bindgen]
+impl MockDao {
+    #[init]
+    pub fn new(proposal_bond: U128) -> Self {
+        Self { proposal_bond: proposal_bond.into(), proposals: Vector::new(b"p".to_vec()) }
+    }
+
+    #[payable]
+    pub fn add_proposal(&mut self, proposal: ProposalInput) -> u64 {
+        assert!(env::attached_deposit() >= self.proposal_bond, "ERR_MIN_BOND");
+
+        self.proposals.push(&Proposal {
+            proposer: env::predecessor_account_id(),
+            description: proposal.description,
+            kind: proposal.kind,
+            status: ProposalStatus::InProgress,
+        });
+
+        self.proposals.len() - 1
+    }
+
+    pub fn get_proposal(&self, id: u64) -> ProposalOutput {
+        let proposal = self.proposals.get(id).expect("ERR_NO_PROPOSAL");
+        ProposalOutput { id, proposal }
+    }
+
+    // Anyone votes, a single vote decides. `action` is one of VoteApprove, VoteReject or VoteRemove.
+    pub fn act_proposal(&mut self, id: u64, action: String) {
+        let mut proposal = self.proposals.get(id).expect("ERR_NO_PROPOSAL");
+        assert_eq!(proposal.status, ProposalStatus::InProgress, "ERR_PROPOSAL_NOT_IN_PROGRESS");
+
+        proposal.status = match action.as_str() {
+            "VoteApprove" => ProposalStatus::Approved,
+            "VoteReject" => ProposalStatus::Rejected,
+            "VoteRemove" => ProposalStatus::Removed,
+            _ => env::panic(b"ERR_UNKNOWN_ACTION"),
+        };
+        self.proposals.replace(id, &proposal);
+
+        if proposal.status != ProposalStatus::Removed {
+            Promise::new(proposal.proposer.clone()).transfer(self.proposal_bond);
+        }
+
+        if proposal.status == ProposalStatus::Approved {
+            let ProposalKind::FunctionCall { receiver_id, actions } = proposal.kind;
+            let mut promise = Promise::new(receiver_id);
+            for action in actions {
+                promise = promise.function_call(
+                    action.method_name.into_bytes(),
+                    action.args.into(),
+                    action.deposit.into(),
+                    action.gas.into(),
+                );
+            }
+        }
+    }
+}
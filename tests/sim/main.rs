@@ -1,4 +1,9 @@
 mod test_approval;
 mod test_core;
 mod test_enumeration;
+mod test_ino;
+mod test_metadata;
+mod test_sale;
+mod test_storage;
+mod test_upgrade;
 mod utils;
@@ -11,7 +11,7 @@ fn simulate_simple_approve() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None),
-        deposit = 170000000000000000000
+        deposit = 1
     )
     .assert_success();
 
@@ -38,7 +38,7 @@ fn simulate_simple_approve() {
     expected_approvals.insert(alice.account_id(), 1);
     assert_eq!(token.approved_account_ids.unwrap(), expected_approvals);
 
-    // root approves alice again, which changes the approval_id and doesn't require as much deposit
+    // root approves alice again, which changes the approval_id
     call!(root, nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None), deposit = 1)
         .assert_success();
 
@@ -51,9 +51,8 @@ fn simulate_simple_approve() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), token_receiver.valid_account_id(), None),
-        // note that token_receiver's account name is longer, and so takes more bytes to store and
-        // therefore requires a larger deposit!
-        deposit = 260000000000000000000
+        // storage comes out of root's storage balance whatever the account name length
+        deposit = 1
     )
     .assert_success();
 
@@ -74,7 +73,7 @@ fn simulate_approval_with_call() {
             approval_receiver.valid_account_id(),
             Some("return-now".to_string())
         ),
-        deposit = 290000000000000000000
+        deposit = 1
     );
     assert!(outcome.is_ok());
     let res: String = outcome.unwrap_json();
@@ -102,7 +101,7 @@ fn simulate_approved_account_transfers_token() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None),
-        deposit = 170000000000000000000
+        deposit = 1
     )
     .assert_success();
 
@@ -132,7 +131,7 @@ fn simulate_revoke() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None),
-        deposit = 170000000000000000000
+        deposit = 1
     )
     .assert_success();
 
@@ -140,7 +139,7 @@ fn simulate_revoke() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), token_receiver.valid_account_id(), None),
-        deposit = 260000000000000000000
+        deposit = 1
     )
     .assert_success();
 
@@ -183,7 +182,7 @@ fn simulate_revoke_all() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None),
-        deposit = 170000000000000000000
+        deposit = 1
     )
     .assert_success();
 
@@ -191,7 +190,7 @@ fn simulate_revoke_all() {
     call!(
         root,
         nft.nft_approve(TOKEN_ID.into(), token_receiver.valid_account_id(), None),
-        deposit = 260000000000000000000
+        deposit = 1
     )
     .assert_success();
 
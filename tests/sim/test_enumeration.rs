@@ -1,36 +1,21 @@
-use crate::utils::{helper_mint, init};
+use crate::utils::{helper_mint, init_with_count};
 use near_contract_standards::non_fungible_token::Token;
 use near_sdk::json_types::U128;
 use near_sdk_sim::{view, ContractAccount, UserAccount};
 use non_fungible_token::ContractContract as NftContract;
 
+const COLLECTION_SIZE: u128 = 4;
+
+// Mints the rest of the collection, ids are picked at random so only the full set is known
 fn mint_more(root: &UserAccount, nft: &ContractAccount<NftContract>) {
-    helper_mint(
-        "1".to_string(),
-        &root,
-        &nft,
-        "Black as the Night".to_string(),
-        "In charcoal".to_string(),
-    );
-    helper_mint(
-        "2".to_string(),
-        &root,
-        &nft,
-        "Hamakua".to_string(),
-        "Vintage recording".to_string(),
-    );
-    helper_mint(
-        "3".to_string(),
-        &root,
-        &nft,
-        "Aloha ke akua".to_string(),
-        "Original with piano".to_string(),
-    );
+    helper_mint(&root, &nft);
+    helper_mint(&root, &nft);
+    helper_mint(&root, &nft);
 }
 
 #[test]
 fn simulate_enum_total_supply() {
-    let (root, nft, _, _, _) = init();
+    let (root, nft, _, _, _) = init_with_count(COLLECTION_SIZE);
     mint_more(&root, &nft);
 
     let total_supply: U128 = view!(nft.nft_total_supply()).unwrap_json();
@@ -39,7 +24,7 @@ fn simulate_enum_total_supply() {
 
 #[test]
 fn simulate_enum_nft_tokens() {
-    let (root, nft, _, _, _) = init();
+    let (root, nft, _, _, _) = init_with_count(COLLECTION_SIZE);
     mint_more(&root, &nft);
 
     // No optional args should return all
@@ -66,7 +51,7 @@ fn simulate_enum_nft_tokens() {
 
 #[test]
 fn simulate_enum_nft_supply_for_owner() {
-    let (root, nft, alice, _, _) = init();
+    let (root, nft, alice, _, _) = init_with_count(COLLECTION_SIZE);
 
     // Get number from account with no NFTs
     let mut owner_num_tokens: U128 =
@@ -84,7 +69,7 @@ fn simulate_enum_nft_supply_for_owner() {
 
 #[test]
 fn simulate_enum_nft_tokens_for_owner() {
-    let (root, nft, alice, _, _) = init();
+    let (root, nft, alice, _, _) = init_with_count(COLLECTION_SIZE);
     mint_more(&root, &nft);
 
     // Get tokens from account with no NFTs
@@ -93,30 +78,31 @@ fn simulate_enum_nft_tokens_for_owner() {
     assert_eq!(owner_tokens.len(), 0);
 
     // Get tokens with no optional args
-    owner_tokens =
+    let all_tokens: Vec<Token> =
         view!(nft.nft_tokens_for_owner(root.valid_account_id(), None, None)).unwrap_json();
-    assert_eq!(owner_tokens.len(), 4);
+    assert_eq!(all_tokens.len(), 4);
 
+    // Owner tokens are listed in mint order, so pages are compared against the full listing
     // With from_index and no limit
     owner_tokens =
         view!(nft.nft_tokens_for_owner(root.valid_account_id(), Some(U128::from(2)), None))
             .unwrap_json();
     assert_eq!(owner_tokens.len(), 2);
-    assert_eq!(owner_tokens.get(0).unwrap().token_id, "2".to_string());
-    assert_eq!(owner_tokens.get(1).unwrap().token_id, "3".to_string());
+    assert_eq!(owner_tokens.get(0).unwrap().token_id, all_tokens[2].token_id);
+    assert_eq!(owner_tokens.get(1).unwrap().token_id, all_tokens[3].token_id);
 
     // With from_index and limit 1
     owner_tokens =
         view!(nft.nft_tokens_for_owner(root.valid_account_id(), Some(U128::from(1)), Some(1)))
             .unwrap_json();
     assert_eq!(owner_tokens.len(), 1);
-    assert_eq!(owner_tokens.get(0).unwrap().token_id, "1".to_string());
+    assert_eq!(owner_tokens.get(0).unwrap().token_id, all_tokens[1].token_id);
 
     // No from_index but limit 3
     owner_tokens =
         view!(nft.nft_tokens_for_owner(root.valid_account_id(), None, Some(3))).unwrap_json();
     assert_eq!(owner_tokens.len(), 3);
-    assert_eq!(owner_tokens.get(0).unwrap().token_id, "0".to_string());
-    assert_eq!(owner_tokens.get(1).unwrap().token_id, "1".to_string());
-    assert_eq!(owner_tokens.get(2).unwrap().token_id, "2".to_string());
+    assert_eq!(owner_tokens.get(0).unwrap().token_id, all_tokens[0].token_id);
+    assert_eq!(owner_tokens.get(1).unwrap().token_id, all_tokens[1].token_id);
+    assert_eq!(owner_tokens.get(2).unwrap().token_id, all_tokens[2].token_id);
 }
@@ -0,0 +1,550 @@
+use crate::utils::{
+    assert_failure, deploy_dao, deploy_nft, deploy_nft_with_id, helper_mint, init_ino, DAO_ID, MINT_PRICE,
+    MINT_PRICE_YOCTO,
+};
+use ino_common::ContractError;
+use ino::{Collection, CollectionStatsView, CollectionStatus, CollectionView, DaoConfig};
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
+use near_sdk::json_types::{U128, U64};
+use near_sdk::AccountId;
+use near_sdk_sim::{call, to_yocto, view, DEFAULT_GAS};
+use non_fungible_token::{MintCost, MintGate, VestingView};
+
+fn sample_collection() -> Collection {
+    Collection {
+        name: "Dao Nation".to_string(),
+        symbol: "DAON".to_string(),
+        url: "https://daonation.io".to_string(),
+        total_count: U128(1000),
+        price: U128(100),
+        contract: "nft".to_string(),
+    }
+}
+
+#[test]
+fn simulate_add_and_approve_collection() {
+    let (root, ino, alice) = init_ino();
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+
+    let (collections, status): (Vec<Collection>, Vec<bool>) =
+        view!(ino.get_collection()).unwrap_json();
+    assert_eq!(collections.len(), 1);
+    assert_eq!(collections[0].name, "Dao Nation".to_string());
+    assert_eq!(status, vec![false]);
+
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    let (_, status): (Vec<Collection>, Vec<bool>) = view!(ino.get_collection()).unwrap_json();
+    assert_eq!(status, vec![true]);
+
+    // mint reports are tied to a single collection per contract
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+    let outcome = call!(root, ino.update_collection_status(Some(U128(1))));
+    assert_failure(outcome, ContractError::ContractAlreadyListed);
+}
+
+#[test]
+fn simulate_only_curator_updates_status() {
+    let (root, ino, alice) = init_ino();
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+
+    let outcome = call!(alice, ino.update_collection_status(Some(U128(0))));
+    assert_failure(outcome, ContractError::MissingRole);
+
+    call!(root, ino.grant_role(alice.valid_account_id(), ino::Role::Curator)).assert_success();
+    call!(alice, ino.update_collection_status(Some(U128(0)))).assert_success();
+}
+
+#[test]
+fn simulate_update_latest_collection_status() {
+    let (root, ino, alice) = init_ino();
+
+    let outcome = call!(root, ino.update_collection_status(None));
+    assert_failure(outcome, ContractError::InvalidCollectionId);
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+    call!(root, ino.update_collection_status(None)).assert_success();
+
+    let (_, status): (Vec<Collection>, Vec<bool>) = view!(ino.get_collection()).unwrap_json();
+    assert_eq!(status, vec![true]);
+}
+
+#[test]
+fn simulate_paused_submissions() {
+    let (root, ino, alice) = init_ino();
+
+    call!(root, ino.set_submissions_paused(true)).assert_success();
+
+    let outcome = call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"));
+    assert_failure(outcome, ContractError::SubmissionsPaused);
+
+    call!(root, ino.set_submissions_paused(false)).assert_success();
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+}
+
+#[test]
+fn simulate_ownership_transfer() {
+    let (root, ino, alice) = init_ino();
+
+    call!(root, ino.propose_owner(alice.valid_account_id())).assert_success();
+
+    // the owner does not change until the pending owner accepts
+    let owner: AccountId = view!(ino.get_owner()).unwrap_json();
+    assert_eq!(owner, root.account_id());
+
+    call!(alice, ino.accept_ownership()).assert_success();
+
+    let owner: AccountId = view!(ino.get_owner()).unwrap_json();
+    assert_eq!(owner, alice.account_id());
+}
+
+#[test]
+fn simulate_large_collection_values() {
+    let (_, ino, alice) = init_ino();
+
+    let mut collection = sample_collection();
+    collection.total_count = U128(u64::MAX as u128 + 1);
+    collection.price = U128(1_000_000_000_000_000_000_000_000_000);
+
+    call!(alice, ino.add_collection(collection), deposit = to_yocto("1")).assert_success();
+
+    // numbers come back as strings with every digit intact
+    let result = view!(ino.get_collection()).unwrap_json_value();
+    assert_eq!(result[0][0]["total_count"].as_str(), Some("18446744073709551616"));
+    assert_eq!(result[0][0]["price"].as_str(), Some("1000000000000000000000000000"));
+}
+
+#[test]
+fn simulate_invalid_collections_are_rejected() {
+    let (_, ino, alice) = init_ino();
+
+    let cases: Vec<(Box<dyn Fn(&mut Collection)>, ContractError)> = vec![
+        (Box::new(|c| c.name = "  ".to_string()), ContractError::InvalidCollectionName),
+        (Box::new(|c| c.name = "x".repeat(65)), ContractError::InvalidCollectionName),
+        (Box::new(|c| c.symbol = "DAO-N".to_string()), ContractError::InvalidCollectionSymbol),
+        (Box::new(|c| c.url = "http://daonation.io".to_string()), ContractError::InvalidCollectionUrl),
+        (Box::new(|c| c.url = "javascript:alert(1)".to_string()), ContractError::InvalidCollectionUrl),
+        (Box::new(|c| c.total_count = U128(0)), ContractError::InvalidCollectionTotalCount),
+        (Box::new(|c| c.price = U128(0)), ContractError::InvalidCollectionPrice),
+        (Box::new(|c| c.contract = "Not An Account".to_string()), ContractError::InvalidCollectionContract),
+    ];
+
+    for (mutate, error) in cases {
+        let mut collection = sample_collection();
+        mutate(&mut collection);
+
+        let outcome = call!(alice, ino.add_collection(collection), deposit = to_yocto("1"));
+        assert_failure(outcome, error);
+    }
+
+    let (collections, _): (Vec<Collection>, Vec<bool>) = view!(ino.get_collection()).unwrap_json();
+    assert!(collections.is_empty());
+}
+
+#[test]
+fn simulate_storage_is_prepaid_and_released() {
+    let (_, ino, alice) = init_ino();
+
+    let bounds: StorageBalanceBounds = view!(ino.storage_balance_bounds()).unwrap_json();
+    let estimate: U128 = view!(ino.estimate_collection_storage(sample_collection())).unwrap_json();
+
+    // unregistered creators have to attach enough to register
+    let outcome = call!(alice, ino.add_collection(sample_collection()));
+    assert_failure(outcome, ContractError::StorageNotRegistered);
+
+    call!(alice, ino.storage_deposit(None, None), deposit = bounds.min.0 + estimate.0).assert_success();
+    call!(alice, ino.add_collection(sample_collection())).assert_success();
+
+    let balance: StorageBalance = view!(ino.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert_eq!(balance.total.0, bounds.min.0 + estimate.0);
+    assert!(balance.available.0 < estimate.0);
+
+    // the balance does not cover a second collection
+    let outcome = call!(alice, ino.add_collection(sample_collection()));
+    assert_failure(outcome, ContractError::InsufficientStorageBalance);
+
+    let outcome = call!(alice, ino.storage_unregister(None), deposit = 1);
+    assert_failure(outcome, ContractError::StorageStillInUse);
+
+    call!(alice, ino.remove_collection(U128(0))).assert_success();
+
+    let balance: StorageBalance = view!(ino.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert_eq!(balance.available.0, estimate.0);
+
+    let (collections, _): (Vec<Collection>, Vec<bool>) = view!(ino.get_collection()).unwrap_json();
+    assert!(collections.is_empty());
+
+    call!(alice, ino.storage_unregister(None), deposit = 1).assert_success();
+    let balance: Option<StorageBalance> = view!(ino.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert!(balance.is_none());
+}
+
+#[test]
+fn simulate_only_creator_removes_pending_collection() {
+    let (root, ino, alice) = init_ino();
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1"))
+        .assert_success();
+
+    let outcome = call!(root, ino.remove_collection(U128(0)));
+    assert_failure(outcome, ContractError::NotCollectionCreator);
+
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    let outcome = call!(alice, ino.remove_collection(U128(0)));
+    assert_failure(outcome, ContractError::CollectionNotPending);
+}
+
+#[test]
+fn simulate_submission_bond_is_escrowed() {
+    let (root, ino, alice) = init_ino();
+
+    let bond = to_yocto("5");
+    call!(root, ino.set_submission_bond(U128(bond))).assert_success();
+
+    let outcome = call!(alice, ino.add_collection(sample_collection()), deposit = bond);
+    assert_failure(outcome, ContractError::StorageNotRegistered);
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = bond + to_yocto("1"))
+        .assert_success();
+    let escrowed: U128 = view!(ino.get_collection_bond(U128(0))).unwrap_json();
+    assert_eq!(escrowed.0, bond);
+
+    // approval hands the whole bond back
+    let balance_before = alice.account().unwrap().amount;
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+    assert_eq!(alice.account().unwrap().amount, balance_before + bond);
+
+    let escrowed: U128 = view!(ino.get_collection_bond(U128(0))).unwrap_json();
+    assert_eq!(escrowed.0, 0);
+
+    let outcome = call!(root, ino.reject_collection(U128(0), None, "spam".to_string()));
+    assert_failure(outcome, ContractError::CollectionNotPending);
+
+    // so does withdrawing a pending submission
+    call!(alice, ino.add_collection(sample_collection()), deposit = bond).assert_success();
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.remove_collection(U128(1))).assert_success();
+    assert!(alice.account().unwrap().amount > balance_before + bond - to_yocto("0.01"));
+}
+
+#[test]
+fn simulate_rejection_slashes_bond() {
+    let (root, ino, alice) = init_ino();
+
+    let bond = to_yocto("5");
+    call!(root, ino.set_submission_bond(U128(bond))).assert_success();
+    call!(alice, ino.add_collection(sample_collection()), deposit = bond + to_yocto("1"))
+        .assert_success();
+
+    let outcome = call!(root, ino.reject_collection(U128(0), Some(U128(bond + 1)), "spam".to_string()));
+    assert_failure(outcome, ContractError::SlashTooLarge);
+
+    let balance_before = alice.account().unwrap().amount;
+    call!(root, ino.reject_collection(U128(0), Some(U128(to_yocto("2"))), "spam".to_string()))
+        .assert_success();
+    assert_eq!(alice.account().unwrap().amount, balance_before + to_yocto("3"));
+
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert_eq!(collections[0].status, CollectionStatus::Rejected);
+
+    let treasury: U128 = view!(ino.get_treasury_balance()).unwrap_json();
+    assert_eq!(treasury.0, to_yocto("2"));
+
+    let outcome = call!(root, ino.withdraw(U128(to_yocto("3")), None));
+    assert_failure(outcome, ContractError::WithdrawTooLarge);
+    call!(root, ino.withdraw(U128(to_yocto("2")), None)).assert_success();
+
+    // rejected collections can still be removed to free their storage
+    call!(alice, ino.remove_collection(U128(0))).assert_success();
+}
+
+#[test]
+fn simulate_buy_through_launchpad() {
+    let (root, ino, alice) = init_ino();
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+
+    let mut collection = sample_collection();
+    collection.total_count = U128(3);
+    call!(alice, ino.add_collection(collection), deposit = to_yocto("1")).assert_success();
+
+    let outcome = call!(alice, ino.buy(U128(0), alice.valid_account_id(), 1), deposit = to_yocto("2"), gas = DEFAULT_GAS);
+    assert_failure(outcome, ContractError::CollectionNotApproved);
+
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    let outcome = call!(alice, ino.buy(U128(0), alice.valid_account_id(), 2), deposit = to_yocto("5"), gas = 50_000_000_000_000);
+    assert_failure(outcome, ContractError::InsufficientGas);
+
+    let cost: MintCost = view!(nft.get_mint_cost(alice.valid_account_id(), Some(2))).unwrap_json();
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 2), deposit = cost.total.0 + to_yocto("1"), gas = DEFAULT_GAS)
+        .assert_success();
+
+    // the surplus came back, only the cost and gas were spent
+    let spent = balance_before - alice.account().unwrap().amount;
+    assert!(spent >= cost.total.0 && spent < cost.total.0 + to_yocto("0.1"));
+
+    let owned: U128 = view!(nft.nft_supply_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(owned.0, 2);
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 2);
+
+    // an underfunded purchase is refunded in full
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 1), deposit = MINT_PRICE_YOCTO - 1, gas = DEFAULT_GAS)
+        .assert_success();
+    assert!(balance_before - alice.account().unwrap().amount < to_yocto("0.1"));
+
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 2);
+
+    let outcome = call!(alice, ino.buy(U128(0), alice.valid_account_id(), 2), deposit = to_yocto("5"), gas = DEFAULT_GAS);
+    assert_failure(outcome, ContractError::SoldOut);
+}
+
+#[test]
+fn simulate_buy_gated_collection() {
+    let (root, ino, alice) = init_ino();
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+    let gate = deploy_nft_with_id(&root, "gate", MINT_PRICE, 3);
+
+    call!(
+        root,
+        nft.set_mint_gate(Some(MintGate::NonFungibleToken { contract_id: "gate".to_string(), min_tokens: U128(1) }))
+    )
+    .assert_success();
+
+    let mut collection = sample_collection();
+    collection.total_count = U128(3);
+    call!(alice, ino.add_collection(collection), deposit = to_yocto("1")).assert_success();
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    // several gated mints are refused before anything is minted, the whole deposit comes back
+    let cost: MintCost = view!(nft.get_mint_cost(alice.valid_account_id(), Some(2))).unwrap_json();
+    assert!(cost.gated);
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 2), deposit = cost.total.0, gas = DEFAULT_GAS)
+        .assert_success();
+
+    assert!(balance_before - alice.account().unwrap().amount < to_yocto("0.1"));
+    let storage: Option<StorageBalance> = view!(nft.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert!(storage.is_none());
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 0);
+
+    // alice holds nothing of the gate collection, so all but the storage deposit comes back to her
+    let cost: MintCost = view!(nft.get_mint_cost(alice.valid_account_id(), Some(1))).unwrap_json();
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 1), deposit = cost.total.0, gas = DEFAULT_GAS)
+        .assert_success();
+
+    let spent = balance_before - alice.account().unwrap().amount;
+    assert!(spent >= cost.storage.0 && spent < cost.storage.0 + to_yocto("0.1"));
+    let storage: Option<StorageBalance> = view!(nft.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert_eq!(storage.unwrap().total, cost.storage);
+
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 0);
+    let owned: U128 = view!(nft.nft_supply_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(owned.0, 0);
+
+    // once she holds a gate token single purchases go through
+    helper_mint(&alice, &gate);
+
+    let cost: MintCost = view!(nft.get_mint_cost(alice.valid_account_id(), Some(2))).unwrap_json();
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 2), deposit = cost.total.0, gas = DEFAULT_GAS)
+        .assert_success();
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 0);
+
+    let cost: MintCost = view!(nft.get_mint_cost(alice.valid_account_id(), Some(1))).unwrap_json();
+    call!(alice, ino.buy(U128(0), alice.valid_account_id(), 1), deposit = cost.total.0, gas = DEFAULT_GAS)
+        .assert_success();
+
+    let sold: U128 = view!(ino.get_sold(U128(0))).unwrap_json();
+    assert_eq!(sold.0, 1);
+    let owned: U128 = view!(nft.nft_supply_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(owned.0, 1);
+}
+
+#[test]
+fn simulate_sales_stats() {
+    let (root, ino, alice) = init_ino();
+    let nft = deploy_nft(&root, MINT_PRICE, 2);
+
+    let mut collection = sample_collection();
+    collection.total_count = U128(2);
+    call!(alice, ino.add_collection(collection), deposit = to_yocto("1")).assert_success();
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    // without reports the stats only move on refresh
+    helper_mint(&alice, &nft);
+    let stats: CollectionStatsView = view!(ino.get_collection_stats(U128(0))).unwrap_json();
+    assert_eq!(stats.minted.0, 0);
+
+    call!(alice, ino.refresh_stats(U128(0)), gas = DEFAULT_GAS).assert_success();
+    let stats: CollectionStatsView = view!(ino.get_collection_stats(U128(0))).unwrap_json();
+    assert_eq!(stats.minted.0, 1);
+
+    // once linked, each mint is reported with what it was paid
+    call!(root, nft.set_launchpad(Some(ino.valid_account_id()))).assert_success();
+    helper_mint(&alice, &nft);
+
+    let stats: CollectionStatsView = view!(ino.get_collection_stats(U128(0))).unwrap_json();
+    assert_eq!(stats.minted.0, 2);
+    assert_eq!(stats.revenue.0, MINT_PRICE_YOCTO);
+
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert_eq!(collections[0].status, CollectionStatus::SoldOut);
+    assert!(collections[0].approved);
+
+    let outcome = call!(alice, ino.buy(U128(0), alice.valid_account_id(), 1), deposit = to_yocto("2"), gas = DEFAULT_GAS);
+    assert_failure(outcome, ContractError::SoldOut);
+
+    // only linked contracts can report
+    let outcome = call!(alice, ino.on_nft_minted(U128(5), U128(1)));
+    assert_failure(outcome, ContractError::InvalidCollectionContract);
+}
+
+#[test]
+fn simulate_flagging_freezes_vesting() {
+    let (root, ino, alice) = init_ino();
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+
+    call!(root, nft.set_vesting(alice.valid_account_id(), U64(0), U64(0), U64(1_000_000_000_000))).assert_success();
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1")).assert_success();
+    call!(root, ino.update_collection_status(Some(U128(0)))).assert_success();
+
+    let outcome = call!(alice, ino.flag_collection(U128(0), true, "copied artwork".to_string()));
+    assert_failure(outcome, ContractError::MissingRole);
+    let outcome = call!(root, ino.flag_collection(U128(0), true, "a".repeat(281)));
+    assert_failure(outcome, ContractError::InvalidFlagReason);
+
+    // the contract doesn't list this registry as its launchpad yet, so the flag doesn't stick
+    let flagged: bool = call!(root, ino.flag_collection(U128(0), true, "copied artwork".to_string())).unwrap_json();
+    assert!(!flagged);
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert!(!collections[0].flagged);
+
+    call!(root, nft.set_launchpad(Some(ino.valid_account_id()))).assert_success();
+    helper_mint(&alice, &nft);
+    call!(root, ino.flag_collection(U128(0), true, "copied artwork".to_string())).assert_success();
+
+    let vesting: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert!(vesting.frozen);
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert!(collections[0].flagged);
+
+    // proceeds of mints made while flagged don't vest
+    helper_mint(&alice, &nft);
+    let still_frozen: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert_eq!(still_frozen.total.0, 2 * MINT_PRICE_YOCTO);
+    assert_eq!(still_frozen.vested, vesting.vested);
+
+    // the owner can't lift it, only unflagging does
+    let outcome = call!(root, nft.set_vesting_frozen(false));
+    assert_failure(outcome, ContractError::NotLaunchpad);
+
+    call!(root, ino.flag_collection(U128(0), false, String::new())).assert_success();
+    let vesting: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert!(!vesting.frozen);
+}
+
+fn dao_config(proposal_bond: u128) -> DaoConfig {
+    DaoConfig { account_id: DAO_ID.to_string(), proposal_bond: U128(proposal_bond), auto_propose: true }
+}
+
+#[test]
+fn simulate_dao_approval_returns_proposal_bond() {
+    let (root, ino, alice) = init_ino();
+    let dao = deploy_dao(&root, to_yocto("1"));
+
+    call!(root, ino.set_dao_config(Some(dao_config(to_yocto("1"))))).assert_success();
+
+    // the proposal bond comes on top of the storage deposit
+    let outcome = call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("0.5"));
+    assert_failure(outcome, ContractError::InsufficientDeposit);
+
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1.5")).assert_success();
+
+    let proposal_id: Option<U64> = view!(ino.get_proposal_id(U128(0))).unwrap_json();
+    assert_eq!(proposal_id.map(|id| id.0), Some(0));
+    let bond: U128 = view!(ino.get_proposal_bond(U128(0))).unwrap_json();
+    assert_eq!(bond.0, to_yocto("1"));
+
+    // only the DAO approves now
+    let outcome = call!(root, ino.update_collection_status(Some(U128(0))));
+    assert_failure(outcome, ContractError::NotDao);
+
+    // the DAO still holds the bond while the proposal is open
+    call!(root, ino.release_proposal_bond(U128(0))).assert_success();
+    let bond: U128 = view!(ino.get_proposal_bond(U128(0))).unwrap_json();
+    assert_eq!(bond.0, to_yocto("1"));
+
+    call!(root, dao.act_proposal(0, "VoteApprove".to_string())).assert_success();
+
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert_eq!(collections[0].status, CollectionStatus::Approved);
+
+    let balance_before = alice.account().unwrap().amount;
+    call!(root, ino.release_proposal_bond(U128(0))).assert_success();
+    assert_eq!(alice.account().unwrap().amount, balance_before + to_yocto("1"));
+
+    let outcome = call!(root, ino.release_proposal_bond(U128(0)));
+    assert_failure(outcome, ContractError::NothingToRefund);
+}
+
+#[test]
+fn simulate_curators_reject_under_a_dao() {
+    let (root, ino, alice) = init_ino();
+    deploy_dao(&root, to_yocto("1"));
+
+    call!(root, ino.set_dao_config(Some(dao_config(to_yocto("1"))))).assert_success();
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1.5")).assert_success();
+
+    let outcome = call!(alice, ino.reject_collection(U128(0), None, "spam".to_string()));
+    assert_failure(outcome, ContractError::MissingRole);
+
+    call!(root, ino.reject_collection(U128(0), None, "spam".to_string())).assert_success();
+
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert_eq!(collections[0].status, CollectionStatus::Rejected);
+}
+
+#[test]
+fn simulate_dao_proposal_bond_outcomes() {
+    let (root, ino, alice) = init_ino();
+    let dao = deploy_dao(&root, to_yocto("1"));
+
+    // a bond below what the DAO asks makes the proposal fail, the submitter gets it back
+    call!(root, ino.set_dao_config(Some(dao_config(to_yocto("0.5"))))).assert_success();
+
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1")).assert_success();
+    assert!(balance_before - alice.account().unwrap().amount < to_yocto("0.6"));
+
+    let proposal_id: Option<U64> = view!(ino.get_proposal_id(U128(0))).unwrap_json();
+    assert!(proposal_id.is_none());
+    let bond: U128 = view!(ino.get_proposal_bond(U128(0))).unwrap_json();
+    assert_eq!(bond.0, 0);
+
+    // a removed proposal keeps its bond on the DAO
+    call!(root, ino.set_dao_config(Some(dao_config(to_yocto("1"))))).assert_success();
+    call!(alice, ino.add_collection(sample_collection()), deposit = to_yocto("1.5")).assert_success();
+    call!(root, dao.act_proposal(0, "VoteRemove".to_string())).assert_success();
+
+    let balance_before = alice.account().unwrap().amount;
+    call!(root, ino.release_proposal_bond(U128(1))).assert_success();
+    assert_eq!(alice.account().unwrap().amount, balance_before);
+    let bond: U128 = view!(ino.get_proposal_bond(U128(1))).unwrap_json();
+    assert_eq!(bond.0, 0);
+}
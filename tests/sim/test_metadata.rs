@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use crate::utils::{
+    assert_failure, collection_metadata, deploy_nft, deploy_nft_with_id, helper_mint, init, MINT_DEPOSIT, MINT_PRICE,
+    TOKEN_ID,
+};
+use ino_common::ContractError;
+use near_contract_standards::non_fungible_token::metadata::NFTContractMetadata;
+use near_contract_standards::non_fungible_token::Token;
+use near_sdk::json_types::{U128, U64};
+use near_sdk_sim::{call, init_simulator, to_yocto, view, ContractAccount};
+use non_fungible_token::ContractContract as NftContract;
+use non_fungible_token::{
+    MetadataProvenance, MetadataTemplate, TokenAttribute, TokenMetadataProgress, TokenMetadataUpload,
+};
+
+#[test]
+fn simulate_update_base_uri() {
+    let (root, nft, alice, _, _) = init();
+
+    let outcome = call!(alice, nft.set_base_uri(Some("https://gateway.example/ipfs".to_string())));
+    assert_failure(outcome, ContractError::NotOwner);
+
+    call!(root, nft.set_base_uri(Some("https://gateway.example/ipfs".to_string())))
+        .assert_success();
+
+    let metadata: NFTContractMetadata = view!(nft.nft_metadata()).unwrap_json();
+    assert_eq!(metadata.base_uri, Some("https://gateway.example/ipfs".to_string()));
+    assert_eq!(metadata.name, collection_metadata().name);
+
+    // the minted token resolves against the new base uri
+    let token: Token = view!(nft.nft_token(TOKEN_ID.into())).unwrap_json();
+    let token_metadata = token.metadata.unwrap();
+    assert_eq!(token_metadata.media, Some(format!("{}.png", TOKEN_ID)));
+    assert_eq!(token_metadata.reference, Some(format!("{}.json", TOKEN_ID)));
+}
+
+#[test]
+fn simulate_frozen_metadata_is_locked() {
+    let (root, nft, _, _, _) = init();
+
+    let frozen: bool = call!(root, nft.freeze_metadata(None)).unwrap_json();
+    assert!(frozen);
+
+    let frozen: bool = view!(nft.is_metadata_frozen()).unwrap_json();
+    assert!(frozen);
+
+    let provenance: Option<MetadataProvenance> = view!(nft.get_metadata_provenance()).unwrap_json();
+    let provenance = provenance.unwrap();
+    assert!(provenance.block_height.0 > 0);
+    assert_eq!(provenance.metadata_hash.0.len(), 32);
+
+    let outcome = call!(root, nft.set_base_uri(None));
+    assert_failure(outcome, ContractError::MetadataFrozen);
+
+    let mut metadata = collection_metadata();
+    metadata.name = "Renamed".to_string();
+    let outcome = call!(root, nft.set_contract_metadata(metadata));
+    assert_failure(outcome, ContractError::MetadataFrozen);
+
+    let outcome = call!(root, nft.set_metadata_template(MetadataTemplate::default()));
+    assert_failure(outcome, ContractError::MetadataFrozen);
+
+    let outcome = call!(root, nft.freeze_metadata(None));
+    assert_failure(outcome, ContractError::MetadataFrozen);
+}
+
+fn metadata_hash(nft: &ContractAccount<NftContract>) -> Vec<u8> {
+    let provenance: Option<MetadataProvenance> = view!(nft.get_metadata_provenance()).unwrap_json();
+    provenance.unwrap().metadata_hash.0
+}
+
+#[test]
+fn simulate_freeze_metadata_in_batches() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 5);
+    let one_shot = deploy_nft_with_id(&root, "one-shot", MINT_PRICE, 5);
+    let retitled = deploy_nft_with_id(&root, "retitled", MINT_PRICE, 5);
+    helper_mint(&root, &nft);
+
+    for _ in 0..2 {
+        let frozen: bool = call!(root, nft.freeze_metadata(Some(U64(2)))).unwrap_json();
+        assert!(!frozen);
+    }
+
+    // metadata is locked while the freeze is underway
+    let frozen: bool = view!(nft.is_metadata_frozen()).unwrap_json();
+    assert!(!frozen);
+    let outcome = call!(root, nft.set_base_uri(None));
+    assert_failure(outcome, ContractError::MetadataFrozen);
+
+    let frozen: bool = call!(root, nft.freeze_metadata(Some(U64(2)))).unwrap_json();
+    assert!(frozen);
+
+    // batching and minted tokens leave the hash unchanged, a different title does not
+    let frozen: bool = call!(root, one_shot.freeze_metadata(None)).unwrap_json();
+    assert!(frozen);
+    assert_eq!(metadata_hash(&nft), metadata_hash(&one_shot));
+
+    let mut template = MetadataTemplate::default();
+    template.title = "Token {id}".to_string();
+    call!(root, retitled.set_metadata_template(template)).assert_success();
+    let frozen: bool = call!(root, retitled.freeze_metadata(None)).unwrap_json();
+    assert!(frozen);
+    assert_ne!(metadata_hash(&nft), metadata_hash(&retitled));
+}
+
+fn upload(token_id: u128, title: &str, attributes: &[(&str, &str)]) -> TokenMetadataUpload {
+    TokenMetadataUpload {
+        token_id: U128(token_id),
+        title: Some(title.to_string()),
+        media_hash: None,
+        reference_hash: None,
+        extra: None,
+        attributes: attributes
+            .iter()
+            .map(|(trait_type, value)| TokenAttribute { trait_type: trait_type.to_string(), value: value.to_string() })
+            .collect(),
+    }
+}
+
+fn trait_count(counts: &HashMap<String, HashMap<String, U64>>, trait_type: &str, value: &str) -> Option<u64> {
+    counts.get(trait_type)?.get(value).map(|count| count.0)
+}
+
+#[test]
+fn simulate_token_metadata_upload_and_replace() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 2);
+
+    let outcome = call!(root, nft.set_token_metadata(vec![upload(2, "Out of range", &[])]), deposit = to_yocto("1"));
+    assert_failure(outcome, ContractError::InvalidTokenMetadata);
+
+    call!(root, nft.set_token_metadata(vec![upload(0, "First", &[("Background", "Blue")])]), deposit = to_yocto("1"))
+        .assert_success();
+
+    let progress: TokenMetadataProgress = view!(nft.get_token_metadata_progress()).unwrap_json();
+    assert_eq!((progress.loaded.0, progress.total.0), (1, 2));
+
+    // a second upload of the same id replaces it and its traits
+    call!(root, nft.set_token_metadata(vec![upload(0, "Replaced", &[("Background", "Red")])]), deposit = to_yocto("1"))
+        .assert_success();
+
+    let stored: Option<TokenMetadataUpload> = view!(nft.get_token_metadata_upload(U128(0))).unwrap_json();
+    assert_eq!(stored.unwrap().title, Some("Replaced".to_string()));
+
+    let progress: TokenMetadataProgress = view!(nft.get_token_metadata_progress()).unwrap_json();
+    assert_eq!(progress.loaded.0, 1);
+
+    let counts: HashMap<String, HashMap<String, U64>> = view!(nft.get_trait_counts(None)).unwrap_json();
+    assert_eq!(trait_count(&counts, "Background", "Red"), Some(1));
+    assert_eq!(trait_count(&counts, "Background", "Blue"), None);
+}
+
+#[test]
+fn simulate_mint_waits_for_every_upload() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 2);
+
+    call!(root, nft.set_token_metadata(vec![upload(0, "Zero", &[])]), deposit = to_yocto("1"))
+        .assert_success();
+
+    let outcome = call!(root, nft.nft_mint(root.valid_account_id()), deposit = to_yocto(MINT_DEPOSIT));
+    assert_failure(outcome, ContractError::MetadataIncomplete);
+
+    call!(root, nft.set_token_metadata(vec![upload(1, "One", &[])]), deposit = to_yocto("1"))
+        .assert_success();
+
+    let token = helper_mint(&root, &nft);
+    let expected = if token.token_id == "0" { "Zero" } else { "One" };
+    assert_eq!(token.metadata.unwrap().title, Some(expected.to_string()));
+
+    // uploads are locked once minting started
+    let outcome = call!(root, nft.set_token_metadata(vec![upload(1, "Late", &[])]), deposit = to_yocto("1"));
+    assert_failure(outcome, ContractError::MetadataLocked);
+}
+
+#[test]
+fn simulate_trait_queries_and_rarity() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+
+    call!(
+        root,
+        nft.set_token_metadata(vec![
+            upload(0, "Zero", &[("Background", "Blue"), ("Eyes", "Laser")]),
+            upload(1, "One", &[("Background", "Blue")]),
+            upload(2, "Two", &[("Background", "Red")]),
+        ]),
+        deposit = to_yocto("1")
+    )
+    .assert_success();
+
+    let counts: HashMap<String, HashMap<String, U64>> = view!(nft.get_trait_counts(None)).unwrap_json();
+    assert_eq!(trait_count(&counts, "Background", "Blue"), Some(2));
+    assert_eq!(trait_count(&counts, "Background", "Red"), Some(1));
+    assert_eq!(trait_count(&counts, "Eyes", "Laser"), Some(1));
+
+    let counts: HashMap<String, HashMap<String, U64>> =
+        view!(nft.get_trait_counts(Some("Eyes".to_string()))).unwrap_json();
+    assert_eq!(counts.len(), 1);
+    assert_eq!(trait_count(&counts, "Eyes", "Laser"), Some(1));
+
+    // 3 ids / 2 blue + 3 ids / 1 laser, in thousandths
+    let score: Option<U128> = view!(nft.get_rarity_score("0".to_string())).unwrap_json();
+    assert_eq!(score.unwrap().0, 1_500 + 3_000);
+    let score: Option<U128> = view!(nft.get_rarity_score("1".to_string())).unwrap_json();
+    assert_eq!(score.unwrap().0, 1_500);
+    let score: Option<U128> = view!(nft.get_rarity_score("2".to_string())).unwrap_json();
+    assert_eq!(score.unwrap().0, 3_000);
+    let score: Option<U128> = view!(nft.get_rarity_score("3".to_string())).unwrap_json();
+    assert!(score.is_none());
+
+    // only minted tokens are listed
+    let tokens: Vec<Token> =
+        view!(nft.nft_tokens_by_trait("Background".to_string(), "Blue".to_string(), None, None)).unwrap_json();
+    assert!(tokens.is_empty());
+
+    for _ in 0..3 {
+        helper_mint(&root, &nft);
+    }
+
+    let tokens: Vec<Token> =
+        view!(nft.nft_tokens_by_trait("Background".to_string(), "Blue".to_string(), None, None)).unwrap_json();
+    let mut token_ids: Vec<String> = tokens.into_iter().map(|token| token.token_id).collect();
+    token_ids.sort();
+    assert_eq!(token_ids, vec!["0".to_string(), "1".to_string()]);
+
+    let tokens: Vec<Token> =
+        view!(nft.nft_tokens_by_trait("Background".to_string(), "Blue".to_string(), Some(U128(1)), Some(5))).unwrap_json();
+    assert_eq!(tokens.len(), 1);
+
+    let tokens: Vec<Token> =
+        view!(nft.nft_tokens_by_trait("Background".to_string(), "Green".to_string(), None, None)).unwrap_json();
+    assert!(tokens.is_empty());
+}
@@ -0,0 +1,397 @@
+use crate::utils::{
+    assert_failure, deploy_nft, deploy_nft_with_id, helper_mint, init, init_with_count, MINT_DEPOSIT, MINT_PRICE,
+    MINT_PRICE_YOCTO, TOKEN_ID,
+};
+use ino_common::ContractError;
+use near_contract_standards::non_fungible_token::Token;
+use non_fungible_token::{
+    MetadataTemplate, MintCountsView, MintGate, RaffleEntryView, RaffleView, SalePhase, SaleState, SoftCapView,
+    VestingView,
+};
+use near_sdk::json_types::{U128, U64};
+use near_sdk_sim::{call, init_simulator, to_yocto, view};
+use std::collections::HashSet;
+
+#[test]
+fn simulate_sellout() {
+    let (root, nft, alice, _, _) = init_with_count(3);
+
+    helper_mint(&alice, &nft);
+    helper_mint(&alice, &nft);
+
+    let minted: U128 = view!(nft.get_minted()).unwrap_json();
+    assert_eq!(minted.0, 3);
+
+    // every id of the collection was handed out exactly once
+    let tokens: Vec<Token> = view!(nft.nft_tokens(None, None)).unwrap_json();
+    let token_ids: HashSet<String> = tokens.into_iter().map(|token| token.token_id).collect();
+    let expected: HashSet<String> = (0..3).map(|id: u128| id.to_string()).collect();
+    assert_eq!(token_ids, expected);
+
+    for id in 0..3 {
+        let is_minted: bool = view!(nft.is_minted(id.to_string())).unwrap_json();
+        assert!(is_minted);
+    }
+    let is_minted: bool = view!(nft.is_minted("3".to_string())).unwrap_json();
+    assert!(!is_minted);
+
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.total.0, 3);
+    assert_eq!(sale_state.minted.0, 3);
+    assert_eq!(sale_state.remaining.0, 0);
+    assert_eq!(sale_state.price.0, MINT_PRICE_YOCTO);
+    assert_eq!(sale_state.phase, SalePhase::SoldOut);
+
+    let counts: MintCountsView =
+        view!(nft.get_mint_counts_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(counts.public.0, 2);
+    assert_eq!(counts.reserved.0, 0);
+
+    let outcome = call!(root, nft.nft_mint(root.valid_account_id()), deposit = to_yocto("2"));
+    assert_failure(outcome, ContractError::SoldOut);
+}
+
+#[test]
+fn simulate_underpayment() {
+    let (_, nft, alice, _, _) = init_with_count(2);
+
+    let outcome =
+        call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = MINT_PRICE_YOCTO - 1);
+    assert_failure(outcome, ContractError::InsufficientDeposit);
+
+    // the price alone does not cover storage for the new token
+    let outcome = call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = MINT_PRICE_YOCTO);
+    assert_failure(outcome, ContractError::InsufficientStorageDeposit);
+
+    let minted: U128 = view!(nft.get_minted()).unwrap_json();
+    assert_eq!(minted.0, 1);
+}
+
+#[test]
+fn simulate_refund_of_excess_deposit() {
+    let (_, nft, alice, _, _) = init_with_count(2);
+
+    let balance_before = alice.account().unwrap().amount;
+
+    call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = to_yocto("10"))
+        .assert_success();
+
+    // alice pays the price plus storage and gas, the rest of the 10 NEAR comes back
+    let spent = balance_before - alice.account().unwrap().amount;
+    assert!(spent >= MINT_PRICE_YOCTO);
+    assert!(spent < MINT_PRICE_YOCTO + to_yocto("0.1"));
+
+    let owner_num_tokens: U128 =
+        view!(nft.nft_supply_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(owner_num_tokens.0, 1);
+}
+
+#[test]
+fn simulate_default_metadata_template() {
+    let (root, nft, _, _, _) = init();
+
+    let token: Token = view!(nft.nft_token(TOKEN_ID.into())).unwrap_json();
+    let metadata = token.metadata.unwrap();
+    assert_eq!(metadata.title, Some("Dao Nation #0".to_string()));
+    assert_eq!(metadata.description, Some("Dao Nation, minted by Dao Nation".to_string()));
+    assert_eq!(metadata.media, Some("0.png".to_string()));
+    assert_eq!(metadata.reference, Some("0.json".to_string()));
+
+    // the template is locked once the first token exists
+    let template = MetadataTemplate {
+        title: "Member {id}".to_string(),
+        description: "{name} membership".to_string(),
+        media_extension: "gif".to_string(),
+        extra: None,
+    };
+    let outcome = call!(root, nft.set_metadata_template(template));
+    assert_failure(outcome, ContractError::MetadataLocked);
+}
+
+#[test]
+fn simulate_large_values_are_json_safe() {
+    let root = init_simulator(None);
+
+    // both are far above 2^53, where JavaScript numbers start losing precision
+    let count: u128 = (1 << 60) + 1;
+    let price: u128 = 10_000_000_000_000_000_000 + 7;
+    let nft = deploy_nft(&root, price, count);
+
+    let sale_state = view!(nft.get_sale_state()).unwrap_json_value();
+    assert_eq!(sale_state["total"].as_str(), Some(count.to_string().as_str()));
+    assert_eq!(sale_state["remaining"].as_str(), Some(count.to_string().as_str()));
+    assert_eq!(
+        sale_state["price"].as_str(),
+        Some((price * 10_000_000_000_000_000).to_string().as_str())
+    );
+
+    let minted = view!(nft.get_minted()).unwrap_json_value();
+    assert_eq!(minted.as_str(), Some("0"));
+
+    // quoting that many mints at this price would overflow
+    let error = view!(nft.get_mint_cost(root.valid_account_id(), Some(u32::MAX))).unwrap_err();
+    assert!(format!("{:?}", error).contains("E309"));
+}
+
+#[test]
+fn simulate_soft_cap_releases_escrow() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    let deadline = root.borrow_runtime().current_block().block_timestamp + 1_000_000_000_000;
+    call!(root, nft.set_soft_cap(U128(2), U64(deadline))).assert_success();
+
+    helper_mint(&alice, &nft);
+
+    let soft_cap: SoftCapView = view!(nft.get_soft_cap()).unwrap_json();
+    assert_eq!(soft_cap.escrowed.0, MINT_PRICE_YOCTO);
+    assert!(!soft_cap.met);
+
+    // escrowed proceeds can't be withdrawn
+    let withdrawable: U128 = view!(nft.get_withdrawable_balance()).unwrap_json();
+    let outcome = call!(root, nft.withdraw(U128(withdrawable.0 + 1), None));
+    assert_failure(outcome, ContractError::WithdrawTooLarge);
+
+    helper_mint(&alice, &nft);
+
+    let soft_cap: SoftCapView = view!(nft.get_soft_cap()).unwrap_json();
+    assert_eq!(soft_cap.escrowed.0, 0);
+    assert!(soft_cap.met);
+
+    let released: U128 = view!(nft.get_withdrawable_balance()).unwrap_json();
+    assert!(released.0 >= withdrawable.0 + 2 * MINT_PRICE_YOCTO - to_yocto("0.1"));
+
+    let outcome = call!(root, nft.set_soft_cap(U128(3), U64(deadline)));
+    assert_failure(outcome, ContractError::SaleLocked);
+}
+
+#[test]
+fn simulate_failed_raise_is_refunded() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    // blocks are a second apart
+    let deadline = root.borrow_runtime().current_block().block_timestamp + 10_000_000_000;
+    call!(root, nft.set_soft_cap(U128(2), U64(deadline))).assert_success();
+
+    let token = helper_mint(&alice, &nft);
+
+    let outcome = call!(alice, nft.claim_refund(token.token_id.clone()), deposit = 1);
+    assert_failure(outcome, ContractError::RefundUnavailable);
+
+    // burning would strand the escrowed price
+    let outcome = call!(alice, nft.nft_burn(token.token_id.clone()), deposit = 1);
+    assert_failure(outcome, ContractError::ProceedsEscrowed);
+
+    root.borrow_runtime_mut().produce_blocks(20).unwrap();
+
+    let outcome = call!(alice, nft.nft_burn(token.token_id.clone()), deposit = 1);
+    assert_failure(outcome, ContractError::ProceedsEscrowed);
+
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.phase, SalePhase::Failed);
+
+    let outcome = call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = to_yocto(MINT_DEPOSIT));
+    assert_failure(outcome, ContractError::SaleEnded);
+
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, nft.claim_refund(token.token_id.clone()), deposit = 1).assert_success();
+    assert!(alice.account().unwrap().amount > balance_before + MINT_PRICE_YOCTO - to_yocto("0.01"));
+
+    let soft_cap: SoftCapView = view!(nft.get_soft_cap()).unwrap_json();
+    assert_eq!(soft_cap.escrowed.0, 0);
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.burned.0, 1);
+}
+
+#[test]
+fn simulate_vested_proceeds() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 3);
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+    let creator = root.create_user("creator".to_string(), to_yocto("10"));
+    let launchpad = root.create_user("launchpad".to_string(), to_yocto("10"));
+
+    // linear over 100 one-second blocks after a 10 second cliff
+    let start = root.borrow_runtime().current_block().block_timestamp;
+    call!(root, nft.set_vesting(creator.valid_account_id(), U64(start), U64(10_000_000_000), U64(100_000_000_000)))
+        .assert_success();
+
+    // nobody could lift a freeze without a launchpad
+    let outcome = call!(root, nft.set_vesting_frozen(true));
+    assert_failure(outcome, ContractError::NoLaunchpad);
+    call!(root, nft.set_launchpad(Some(launchpad.valid_account_id()))).assert_success();
+
+    let withdrawable: U128 = view!(nft.get_withdrawable_balance()).unwrap_json();
+    helper_mint(&alice, &nft);
+    helper_mint(&alice, &nft);
+
+    // proceeds are not part of the treasury
+    let after_mints: U128 = view!(nft.get_withdrawable_balance()).unwrap_json();
+    assert!(after_mints.0 < withdrawable.0 + to_yocto("0.1"));
+
+    let outcome = call!(creator, nft.claim_vested());
+    assert_failure(outcome, ContractError::NothingToClaim);
+    let outcome = call!(alice, nft.claim_vested());
+    assert_failure(outcome, ContractError::NotBeneficiary);
+
+    root.borrow_runtime_mut().produce_blocks(40).unwrap();
+
+    let vesting: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert_eq!(vesting.total.0, 2 * MINT_PRICE_YOCTO);
+    assert!(vesting.vested.0 > 0 && vesting.vested.0 < vesting.total.0);
+
+    call!(creator, nft.claim_vested()).assert_success();
+
+    // while frozen nothing more vests, not even from the proceeds of new mints
+    call!(root, nft.set_vesting_frozen(true)).assert_success();
+    let frozen: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    helper_mint(&alice, &nft);
+    root.borrow_runtime_mut().produce_blocks(100).unwrap();
+    let still_frozen: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert_eq!(still_frozen.total.0, 3 * MINT_PRICE_YOCTO);
+    assert_eq!(still_frozen.vested, frozen.vested);
+
+    // only the launchpad lifts a freeze, and it can't be replaced meanwhile
+    let outcome = call!(root, nft.set_vesting_frozen(false));
+    assert_failure(outcome, ContractError::NotLaunchpad);
+    let outcome = call!(root, nft.set_launchpad(Some(root.valid_account_id())));
+    assert_failure(outcome, ContractError::VestingFrozen);
+
+    call!(launchpad, nft.set_vesting_frozen(false)).assert_success();
+    let vesting: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert_eq!(vesting.vested, vesting.total);
+
+    call!(creator, nft.claim_vested()).assert_success();
+    let vesting: VestingView = view!(nft.get_vesting()).unwrap_json::<Option<VestingView>>().unwrap();
+    assert_eq!(vesting.claimed, vesting.total);
+    assert_eq!(vesting.remaining.0, 0);
+}
+
+#[test]
+fn simulate_raffle() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 10);
+    let entrants: Vec<_> = ["alice", "bob", "carol"]
+        .iter()
+        .map(|name| root.create_user(name.to_string(), to_yocto("100")))
+        .collect();
+
+    // entries for 10 one-second blocks, winners mint for 20 more
+    let now = root.borrow_runtime().current_block().block_timestamp;
+    call!(
+        root,
+        nft.set_raffle(U64(now), U64(now + 10_000_000_000), U64(now + 30_000_000_000), U64(2), 1, U128(to_yocto("1")))
+    )
+    .assert_success();
+
+    for entrant in entrants.iter() {
+        call!(entrant, nft.enter_raffle(), deposit = to_yocto("1.1")).assert_success();
+    }
+    let outcome = call!(entrants[0], nft.enter_raffle(), deposit = to_yocto("1.1"));
+    assert_failure(outcome, ContractError::AlreadyEntered);
+
+    let outcome = call!(entrants[0], nft.nft_mint(entrants[0].valid_account_id()), deposit = to_yocto(MINT_DEPOSIT));
+    assert_failure(outcome, ContractError::RaffleNotDrawn);
+    let outcome = call!(root, nft.draw_raffle());
+    assert_failure(outcome, ContractError::RaffleOpen);
+
+    root.borrow_runtime_mut().produce_blocks(12).unwrap();
+
+    let outcome = call!(root, nft.enter_raffle(), deposit = to_yocto("1.1"));
+    assert_failure(outcome, ContractError::RaffleClosed);
+
+    call!(root, nft.draw_raffle()).assert_success();
+    let outcome = call!(root, nft.draw_raffle());
+    assert_failure(outcome, ContractError::RaffleAlreadyDrawn);
+
+    let raffle: RaffleView = view!(nft.get_raffle()).unwrap_json::<Option<RaffleView>>().unwrap();
+    assert!(raffle.drawn);
+    assert_eq!(raffle.entrants.0, 3);
+
+    let (winners, losers): (Vec<_>, Vec<_>) = entrants.iter().partition(|entrant| {
+        let entry: Option<RaffleEntryView> = view!(nft.get_raffle_entry(entrant.valid_account_id())).unwrap_json();
+        entry.unwrap().won
+    });
+    assert_eq!(winners.len(), 2);
+
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.phase, SalePhase::Raffle);
+
+    // winners mint up to their allowance, losers wait for the public sale
+    helper_mint(winners[0], &nft);
+    let outcome = call!(winners[0], nft.nft_mint(winners[0].valid_account_id()), deposit = to_yocto(MINT_DEPOSIT));
+    assert_failure(outcome, ContractError::NoMintAllowance);
+    let outcome = call!(losers[0], nft.nft_mint(losers[0].valid_account_id()), deposit = to_yocto(MINT_DEPOSIT));
+    assert_failure(outcome, ContractError::NoMintAllowance);
+
+    let counts: MintCountsView = view!(nft.get_mint_counts_for_owner(winners[0].valid_account_id())).unwrap_json();
+    assert_eq!(counts.raffle.0, 1);
+    assert_eq!(counts.public.0, 0);
+
+    let balance_before = losers[0].account().unwrap().amount;
+    let reclaimed: U128 = call!(losers[0], nft.reclaim_raffle_deposit()).unwrap_json();
+    assert_eq!(reclaimed.0, to_yocto("1"));
+    assert!(losers[0].account().unwrap().amount > balance_before + to_yocto("0.99"));
+    let entry: Option<RaffleEntryView> = view!(nft.get_raffle_entry(losers[0].valid_account_id())).unwrap_json();
+    assert!(entry.is_none());
+    let outcome = call!(losers[0], nft.reclaim_raffle_deposit());
+    assert_failure(outcome, ContractError::NothingToRefund);
+
+    root.borrow_runtime_mut().produce_blocks(20).unwrap();
+
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.phase, SalePhase::Open);
+    helper_mint(losers[0], &nft);
+
+    // the winner who never minted still gets the deposit back
+    let reclaimed: U128 = call!(winners[1], nft.reclaim_raffle_deposit()).unwrap_json();
+    assert_eq!(reclaimed.0, to_yocto("1"));
+}
+
+#[test]
+fn simulate_gated_mint() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 10);
+    let gate = deploy_nft_with_id(&root, "gate", MINT_PRICE, 10);
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    let outcome = call!(
+        alice,
+        nft.set_mint_gate(Some(MintGate::NonFungibleToken { contract_id: "gate".to_string(), min_tokens: U128(1) }))
+    );
+    assert_failure(outcome, ContractError::NotOwner);
+    let outcome = call!(
+        root,
+        nft.set_mint_gate(Some(MintGate::NonFungibleToken { contract_id: "gate".to_string(), min_tokens: U128(0) }))
+    );
+    assert_failure(outcome, ContractError::InvalidMintGate);
+
+    call!(
+        root,
+        nft.set_mint_gate(Some(MintGate::NonFungibleToken { contract_id: "gate".to_string(), min_tokens: U128(1) }))
+    )
+    .assert_success();
+
+    // alice holds nothing of the gate collection, so the whole deposit comes back
+    let balance_before = alice.account().unwrap().amount;
+    call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = to_yocto(MINT_DEPOSIT)).assert_success();
+    assert!(balance_before - alice.account().unwrap().amount < to_yocto("0.1"));
+
+    let minted: U128 = view!(nft.get_minted()).unwrap_json();
+    assert_eq!(minted.0, 0);
+
+    helper_mint(&alice, &gate);
+
+    call!(alice, nft.nft_mint(alice.valid_account_id()), deposit = to_yocto(MINT_DEPOSIT)).assert_success();
+    let held: U128 = view!(nft.nft_supply_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(held.0, 1);
+    let counts: MintCountsView = view!(nft.get_mint_counts_for_owner(alice.valid_account_id())).unwrap_json();
+    assert_eq!(counts.public.0, 1);
+
+    // lifting the gate mints directly again
+    call!(root, nft.set_mint_gate(None)).assert_success();
+    helper_mint(&alice, &nft);
+}
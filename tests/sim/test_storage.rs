@@ -0,0 +1,90 @@
+use crate::utils::{assert_failure, init_with_count, TOKEN_ID};
+use ino_common::ContractError;
+use near_contract_standards::storage_management::{StorageBalance, StorageBalanceBounds};
+use near_contract_standards::non_fungible_token::Token;
+use near_sdk::json_types::U128;
+use near_sdk_sim::{call, to_yocto, view};
+use non_fungible_token::{MintCost, SaleState};
+
+#[test]
+fn simulate_mint_charges_measured_storage() {
+    let (root, nft, _, _, _) = init_with_count(3);
+
+    let bob = root.create_user("bob".to_string(), to_yocto("100"));
+
+    let cost: MintCost = view!(nft.get_mint_cost(bob.valid_account_id(), None)).unwrap_json();
+    assert!(cost.storage.0 > 0);
+    assert_eq!(cost.total.0, cost.price.0 + cost.storage.0);
+
+    let outcome = call!(bob, nft.nft_mint(bob.valid_account_id()), deposit = cost.price.0);
+    assert_failure(outcome, ContractError::InsufficientStorageDeposit);
+
+    let token: Token = call!(bob, nft.nft_mint(bob.valid_account_id()), deposit = cost.total.0).unwrap_json();
+
+    // the quote is an upper bound, bob keeps exactly what the token measured and gets the rest back
+    let bounds: StorageBalanceBounds = view!(nft.storage_balance_bounds()).unwrap_json();
+    let balance: StorageBalance = view!(nft.storage_balance_of(bob.valid_account_id())).unwrap_json();
+    assert!(balance.total.0 > bounds.min.0);
+    assert!(balance.total.0 <= cost.storage.0);
+    assert_eq!(balance.available.0, 0);
+
+    // burning gives back all of it
+    call!(bob, nft.nft_burn(token.token_id), deposit = 1).assert_success();
+    let burned: StorageBalance = view!(nft.storage_balance_of(bob.valid_account_id())).unwrap_json();
+    assert_eq!(burned.available.0, balance.total.0 - bounds.min.0);
+
+    // a prepaid balance brings the cost down to the price
+    call!(bob, nft.storage_deposit(None, None), deposit = cost.storage.0).assert_success();
+    let cost: MintCost = view!(nft.get_mint_cost(bob.valid_account_id(), None)).unwrap_json();
+    assert_eq!(cost.storage.0, 0);
+    assert_eq!(cost.total, cost.price);
+}
+
+#[test]
+fn simulate_storage_follows_transfers_and_burns() {
+    let (root, nft, alice, _, _) = init_with_count(1);
+
+    let root_before: StorageBalance = view!(nft.storage_balance_of(root.valid_account_id())).unwrap_json();
+    let alice_before: StorageBalance = view!(nft.storage_balance_of(alice.valid_account_id())).unwrap_json();
+
+    call!(root, nft.nft_approve(TOKEN_ID.into(), alice.valid_account_id(), None), deposit = 1)
+        .assert_success();
+    call!(root, nft.nft_transfer(alice.valid_account_id(), TOKEN_ID.into(), None, None), deposit = 1)
+        .assert_success();
+
+    // root gets back the token and its approval, alice now pays for the token
+    let root_after: StorageBalance = view!(nft.storage_balance_of(root.valid_account_id())).unwrap_json();
+    let alice_after: StorageBalance = view!(nft.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    let token_cost = alice_before.available.0 - alice_after.available.0;
+    assert!(token_cost > 0);
+    assert_eq!(root_after.available.0, root_before.available.0 + token_cost);
+
+    call!(alice, nft.nft_burn(TOKEN_ID.into()), deposit = 1).assert_success();
+
+    let alice_burned: StorageBalance = view!(nft.storage_balance_of(alice.valid_account_id())).unwrap_json();
+    assert_eq!(alice_burned.available, alice_before.available);
+
+    let token: Option<Token> = view!(nft.nft_token(TOKEN_ID.into())).unwrap_json();
+    assert!(token.is_none());
+    let sale_state: SaleState = view!(nft.get_sale_state()).unwrap_json();
+    assert_eq!(sale_state.burned, U128(1));
+
+    call!(alice, nft.storage_unregister(None), deposit = 1).assert_success();
+}
+
+#[test]
+fn simulate_transfers_need_a_funded_receiver() {
+    let (root, nft, _, _, _) = init_with_count(1);
+
+    let bob = root.create_user("bob".to_string(), to_yocto("100"));
+
+    let outcome = call!(root, nft.nft_transfer(bob.valid_account_id(), TOKEN_ID.into(), None, None), deposit = 1);
+    assert_failure(outcome, ContractError::StorageNotRegistered);
+
+    // registration alone does not cover a token
+    call!(bob, nft.storage_deposit(None, Some(true)), deposit = to_yocto("1")).assert_success();
+    let outcome = call!(root, nft.nft_transfer(bob.valid_account_id(), TOKEN_ID.into(), None, None), deposit = 1);
+    assert_failure(outcome, ContractError::InsufficientStorageBalance);
+
+    call!(bob, nft.storage_unregister(None), deposit = 1).assert_success();
+}
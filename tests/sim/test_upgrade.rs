@@ -0,0 +1,66 @@
+use crate::utils::{assert_failure, deploy_nft, helper_mint, init_ino, upgrade_ino, upgrade_nft, MINT_PRICE};
+use ino::{Collection, CollectionView};
+use ino_common::ContractError;
+use near_sdk::json_types::U128;
+use near_sdk_sim::{call, init_simulator, to_yocto, view, DEFAULT_GAS};
+
+#[test]
+fn simulate_nft_upgrade_keeps_state() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 10);
+    let token = helper_mint(&root, &nft);
+
+    upgrade_nft(&root, &nft.account_id(), DEFAULT_GAS).assert_success();
+
+    let version: u16 = view!(nft.get_state_version()).unwrap_json();
+    assert_eq!(version, 3);
+    let minted: U128 = view!(nft.get_minted()).unwrap_json();
+    assert_eq!(minted.0, 1);
+    let is_minted: bool = view!(nft.is_minted(token.token_id)).unwrap_json();
+    assert!(is_minted);
+
+    // minting carries on after the upgrade
+    helper_mint(&root, &nft);
+}
+
+#[test]
+fn simulate_nft_upgrade_requires_owner() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 10);
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    assert_failure(upgrade_nft(&alice, &nft.account_id(), DEFAULT_GAS), ContractError::NotOwner);
+}
+
+#[test]
+fn simulate_nft_upgrade_requires_gas_for_migrate() {
+    let root = init_simulator(None);
+    let nft = deploy_nft(&root, MINT_PRICE, 10);
+
+    assert_failure(upgrade_nft(&root, &nft.account_id(), 25_000_000_000_000), ContractError::InsufficientGas);
+
+    let version: u16 = view!(nft.get_state_version()).unwrap_json();
+    assert_eq!(version, 3);
+}
+
+#[test]
+fn simulate_ino_upgrade_keeps_collections() {
+    let (root, ino, alice) = init_ino();
+
+    let collection = Collection {
+        name: "Dao Nation".to_string(),
+        symbol: "DAON".to_string(),
+        url: "https://daonation.io".to_string(),
+        total_count: U128(1000),
+        price: U128(100),
+        contract: "nft".to_string(),
+    };
+    call!(alice, ino.add_collection(collection), deposit = to_yocto("1")).assert_success();
+
+    assert_failure(upgrade_ino(&alice, &ino.account_id(), DEFAULT_GAS), ContractError::NotOwner);
+    upgrade_ino(&root, &ino.account_id(), DEFAULT_GAS).assert_success();
+
+    let collections: Vec<CollectionView> = view!(ino.get_collections(None, None)).unwrap_json();
+    assert_eq!(collections.len(), 1);
+    assert_eq!(collections[0].collection.symbol, "DAON");
+}
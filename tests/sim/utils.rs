@@ -1,76 +1,90 @@
 use approval_receiver::ApprovalReceiverContract;
-use near_contract_standards::non_fungible_token::metadata::TokenMetadata;
+use ino::INOContract;
+use ino_common::ContractError;
+use near_contract_standards::non_fungible_token::metadata::{NFTContractMetadata, NFT_METADATA_SPEC};
+use near_contract_standards::non_fungible_token::Token;
+use near_sdk::json_types::U128;
+use near_sdk::Gas;
 use non_fungible_token::ContractContract as NftContract;
+use test_dao::MockDaoContract;
 use token_receiver::TokenReceiverContract;
 
-use near_contract_standards::non_fungible_token::TokenId;
-use near_sdk_sim::{call, deploy, init_simulator, to_yocto, ContractAccount, UserAccount};
+use near_sdk_sim::{
+    call, deploy, init_simulator, to_yocto, ContractAccount, ExecutionResult, UserAccount,
+};
 
 // Load in contract bytes at runtime
 near_sdk_sim::lazy_static_include::lazy_static_include_bytes! {
     NFT_WASM_BYTES => "res/non_fungible_token.wasm",
+    INO_WASM_BYTES => "res/ino.wasm",
     TOKEN_RECEIVER_WASM_BYTES => "res/token_receiver.wasm",
     APPROVAL_RECEIVER_WASM_BYTES => "res/approval_receiver.wasm",
+    DAO_WASM_BYTES => "res/test_dao.wasm",
 }
 
 const NFT_ID: &str = "nft";
+const INO_ID: &str = "ino";
 const TOKEN_RECEIVER_ID: &str = "token-receiver";
 const APPROVAL_RECEIVER_ID: &str = "approval-receiver";
+pub const DAO_ID: &str = "dao";
 
-// TODO: how to export String instead of &str? Way too much `into`/`to_string` with &str.
+// `nft_mint` picks a random free id, so a collection of one always mints "0"
 pub const TOKEN_ID: &str = "0";
 
+// Passed to `Contract::new`, which multiplies it by 10^16 yoctoNEAR, i.e. 1 NEAR per token
+pub const MINT_PRICE: u128 = 100;
+pub const MINT_PRICE_YOCTO: u128 = MINT_PRICE * 10_000_000_000_000_000;
+
+// Covers the mint price plus storage, anything left over is refunded
+pub const MINT_DEPOSIT: &str = "2";
+
+// Storage balance `init` gives every account, enough for a few tokens and approvals
+pub const STORAGE_DEPOSIT: &str = "0.1";
+
+pub fn collection_metadata() -> NFTContractMetadata {
+    NFTContractMetadata {
+        spec: NFT_METADATA_SPEC.to_string(),
+        name: "Dao Nation".to_string(),
+        symbol: "DAON".to_string(),
+        icon: None,
+        base_uri: Some("https://ipfs.io/ipfs/daonation".to_string()),
+        reference: None,
+        reference_hash: None,
+    }
+}
+
 /// Initialize simulator and return:
-/// * root: the root user, set as owner_id for NFT contract, owns a token with ID=1
+/// * root: the root user, set as owner_id for NFT contract, owns a token with ID=0
 /// * nft: the NFT contract, callable with `call!` and `view!`
 /// * alice: a user account, does not yet own any tokens
 /// * token_receiver: a contract implementing `nft_on_transfer` for use with `transfer_and_call`
+/// * approval_receiver: a contract implementing `nft_on_approve` for use with `nft_approve`
+///
+/// Every account gets a `STORAGE_DEPOSIT` storage balance on the NFT contract.
 pub fn init() -> (
     UserAccount,
     ContractAccount<NftContract>,
     UserAccount,
     ContractAccount<TokenReceiverContract>,
     ContractAccount<ApprovalReceiverContract>,
+) {
+    init_with_count(1)
+}
+
+/// Same as `init`, but the NFT collection holds `count` tokens of which one is minted to root
+pub fn init_with_count(
+    count: u128,
+) -> (
+    UserAccount,
+    ContractAccount<NftContract>,
+    UserAccount,
+    ContractAccount<TokenReceiverContract>,
+    ContractAccount<ApprovalReceiverContract>,
 ) {
     let root = init_simulator(None);
-    // uses default values for deposit and gas
-    let nft = deploy!(
-        // Contract Proxy
-        contract: NftContract,
-        // Contract account id
-        contract_id: NFT_ID,
-        // Bytes of contract
-        bytes: &NFT_WASM_BYTES,
-        // User deploying the contract,
-        signer_account: root,
-        // init method
-        init_method: new_default_meta(
-            root.valid_account_id()
-        )
-    );
+    let nft = deploy_nft(&root, MINT_PRICE, count);
 
-    call!(
-        root,
-        nft.nft_mint(
-            TOKEN_ID.into(),
-            root.valid_account_id(),
-            TokenMetadata {
-                title: Some("Olympus Mons".into()),
-                description: Some("The tallest mountain in the charted solar system".into()),
-                media: None,
-                media_hash: None,
-                copies: Some(1u64),
-                issued_at: None,
-                expires_at: None,
-                starts_at: None,
-                updated_at: None,
-                extra: None,
-                reference: None,
-                reference_hash: None,
-            }
-        ),
-        deposit = 7000000000000000000000
-    );
+    helper_mint(&root, &nft);
 
     let alice = root.create_user("alice".to_string(), to_yocto("100"));
 
@@ -94,36 +108,94 @@ pub fn init() -> (
         )
     );
 
+    // transfers and approvals are paid from prepaid storage balances
+    for account_id in [root.valid_account_id(), alice.valid_account_id(), token_receiver.valid_account_id(), approval_receiver.valid_account_id()] {
+        call!(root, nft.storage_deposit(Some(account_id), None), deposit = to_yocto(STORAGE_DEPOSIT))
+            .assert_success();
+    }
+
     (root, nft, alice, token_receiver, approval_receiver)
 }
 
-pub fn helper_mint(
-    token_id: TokenId,
-    root: &UserAccount,
-    nft: &ContractAccount<NftContract>,
-    title: String,
-    desc: String,
-) {
-    call!(
-        root,
-        nft.nft_mint(
-            token_id,
+/// Deploy the NFT sale contract owned by `root` without minting anything
+pub fn deploy_nft(root: &UserAccount, price: u128, count: u128) -> ContractAccount<NftContract> {
+    deploy_nft_with_id(root, NFT_ID, price, count)
+}
+
+pub fn deploy_nft_with_id(root: &UserAccount, contract_id: &str, price: u128, count: u128) -> ContractAccount<NftContract> {
+    // uses default values for deposit and gas
+    deploy!(
+        // Contract Proxy
+        contract: NftContract,
+        // Contract account id
+        contract_id: contract_id,
+        // Bytes of contract
+        bytes: &NFT_WASM_BYTES,
+        // User deploying the contract,
+        signer_account: root,
+        // init method
+        init_method: new(
             root.valid_account_id(),
-            TokenMetadata {
-                title: Some(title),
-                description: Some(desc),
-                media: None,
-                media_hash: None,
-                copies: Some(1u64),
-                issued_at: None,
-                expires_at: None,
-                starts_at: None,
-                updated_at: None,
-                extra: None,
-                reference: None,
-                reference_hash: None,
-            }
-        ),
-        deposit = 7000000000000000000000
+            collection_metadata(),
+            U128(price),
+            U128(count),
+            None
+        )
+    )
+}
+
+/// Initialize simulator with only the INO registry, owned by root, plus alice as a creator
+pub fn init_ino() -> (UserAccount, ContractAccount<INOContract>, UserAccount) {
+    let root = init_simulator(None);
+
+    let ino = deploy!(
+        contract: INOContract,
+        contract_id: INO_ID,
+        bytes: &INO_WASM_BYTES,
+        signer_account: root,
+        init_method: new()
     );
+
+    let alice = root.create_user("alice".to_string(), to_yocto("100"));
+
+    (root, ino, alice)
+}
+
+/// Deploys a stub Sputnik DAO that takes `proposal_bond` with every proposal
+pub fn deploy_dao(root: &UserAccount, proposal_bond: u128) -> ContractAccount<MockDaoContract> {
+    deploy!(
+        contract: MockDaoContract,
+        contract_id: DAO_ID,
+        bytes: &DAO_WASM_BYTES,
+        signer_account: root,
+        init_method: new(U128(proposal_bond))
+    )
+}
+
+/// Calls `upgrade` on `contract_id` with the current build of the NFT or INO contract as raw input
+pub fn upgrade_nft(user: &UserAccount, contract_id: &str, gas: Gas) -> ExecutionResult {
+    user.call(contract_id.to_string(), "upgrade", &NFT_WASM_BYTES, gas, 0)
+}
+
+pub fn upgrade_ino(user: &UserAccount, contract_id: &str, gas: Gas) -> ExecutionResult {
+    user.call(contract_id.to_string(), "upgrade", &INO_WASM_BYTES, gas, 0)
+}
+
+pub fn helper_mint(user: &UserAccount, nft: &ContractAccount<NftContract>) -> Token {
+    let outcome = call!(
+        user,
+        nft.nft_mint(user.valid_account_id()),
+        deposit = to_yocto(MINT_DEPOSIT)
+    );
+    outcome.assert_success();
+
+    outcome.unwrap_json()
+}
+
+pub fn assert_failure(outcome: ExecutionResult, error: ContractError) {
+    assert!(!outcome.is_ok(), "Expected the call to fail with {:?}", error);
+
+    let expected = format!("E{}: {}", error.code(), error.message());
+    let status = format!("{:?}", outcome.status());
+    assert!(status.contains(&expected), "Expected failure containing {:?}, got {}", expected, status);
 }